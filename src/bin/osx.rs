@@ -1,10 +1,20 @@
 use clap::Parser; // Import the `Parser` trait from the `clap` crate, used for parsing command-line arguments.
 use colored::Colorize;
 // Import the `Colorize` trait, which allows adding ANSI color codes to strings for terminal output.
-use osx::cli::commands::{Cli, Commands}; // Import the `Cli` struct and `Commands` enum from the `commands` module, which define the CLI structure.
+use osx::cli::commands::{AppsCommand, Cli, Commands}; // Import the `Cli` struct and `Commands` enum from the `commands` module, which define the CLI structure.
 use osx::core::cleaner_orchestrator::clean_my_mac; // Import the `clean_my_mac` function from the `cleaner` module, responsible for system cleanup.
 // use osx::core::cleaner::clean_my_mac; // Import the `clean_my_mac` function from the `cleaner` module, responsible for system cleanup.
-use osx::core::uninstaller::{CliTool, MacApp, Uninstaller}; // Import `CliTool`, `MacApp` structs, and the `Uninstaller` trait from the `uninstaller` module.
+use osx::core::processes::SystemProcesses;
+use osx::core::uninstall_plan::UninstallPlan;
+use osx::core::uninstaller::{AppResolution, CliTool, Uninstaller, resolve_mac_app}; // Import the `CliTool` struct, the app resolver and the `Uninstaller` trait from the `uninstaller` module.
+use osx::core::apps::run_apps_list;
+use osx::core::cleaners::large_files::common_user_dirs;
+use osx::core::duplicates::run_duplicates;
+use osx::core::ios_backups::run_ios_backups;
+use osx::core::macho::run_thin_binaries;
+use osx::core::time_machine::run_snapshots;
+use osx::core::toolchains::run_toolchains;
+use osx::utils::command::SystemRunner;
 use osx::core::version;
 use osx::{log_debug, log_error, log_info, log_warn, logger}; // Import custom logging macros and the `logger` initialization function.
 
@@ -32,30 +42,47 @@ fn main() {
 
     // Use a `match` expression to handle the different subcommands defined in the `Commands` enum.
     match &cli.command { // `&cli.command` takes a reference to the `command` field of the `Cli` struct.
-        Commands::Uninstall { name } => { // If the `uninstall` subcommand was invoked, bind its `name` argument.
+        Commands::Uninstall { name, json, autoremove, force, tool } => { // If the `uninstall` subcommand was invoked, bind its arguments.
             log_info!("🔧 Attempting to uninstall '{}'", name.bright_green()); // Inform the user about the uninstall attempt.
 
-            let app = MacApp::new(name); // Create a `MacApp` uninstaller instance for the given name.
-            let cli_tool = CliTool::new(name); // Create a `CliTool` uninstaller instance for the given name.
+            // Match the name against the installed apps, unless a CLI tool was asked for.
+            let resolution = if *tool { None } else { Some(resolve_mac_app(name)) };
+            let (app, cli_tool) = match resolution {
+                // An installed app is only uninstalled as that app.
+                Some(AppResolution::Installed(app)) => (Some(app.with_autoremove(*autoremove)), None),
+                // Without an installed app, the name may be a CLI tool; the app's leftovers are looked for too.
+                Some(AppResolution::NotInstalled(app)) => (Some(app), Some(CliTool::new(name).with_autoremove(*autoremove))),
+                // Ambiguous names are not uninstalled at all: the candidates were listed.
+                Some(AppResolution::Unresolved) => (None, None),
+                None => (None, Some(CliTool::new(name).with_autoremove(*autoremove))),
+            };
 
-            // Attempt to uninstall the application (GUI app paths).
-            // The `Uninstaller` trait's `uninstall` method is called.
-            if let Err(e) = app.uninstall(dry_run) {
-                // If uninstallation of the Mac app fails, log a warning with the error.
-                log_warn!("Failed to uninstall app '{}': {}", name.bright_yellow(), e.to_string().bright_white());
-            } else {
-                // If uninstallation of the Mac app succeeds, log a success message.
-                log_info!("Successfully uninstalled app '{}'", name.bright_green());
+            let mut uninstallers: Vec<(&str, &dyn Uninstaller)> = Vec::new();
+            if let Some(app) = &app {
+                uninstallers.push(("app", app));
+            }
+            if let Some(cli_tool) = &cli_tool {
+                uninstallers.push(("CLI tool", cli_tool));
             }
 
-            // Attempt to uninstall the command-line tool (CLI tool paths).
-            // This is done separately as a name might correspond to both an app and a CLI tool.
-            if let Err(e) = cli_tool.uninstall(dry_run) {
-                // If uninstallation of the CLI tool fails, log a warning with the error.
-                log_warn!("Failed to uninstall CLI tool '{}': {}", name.bright_yellow(), e.to_string().bright_white());
+            if *json {
+                // Print every plan as JSON: a preview only, nothing is removed.
+                let plans: Vec<UninstallPlan> = uninstallers.iter().map(|(_, u)| u.plan()).collect();
+                match serde_json::to_string_pretty(&plans) {
+                    Ok(output) => println!("{}", output),
+                    Err(e) => log_error!("{}: {}", "Could not serialize the uninstall plan".bright_yellow(), e),
+                }
             } else {
-                // If uninstallation of the CLI tool succeeds, log a success message.
-                log_info!("Successfully uninstalled CLI tool '{}'", name.bright_green());
+                for (kind, uninstaller) in &uninstallers {
+                    // The `Uninstaller` trait's `uninstall` method shows the plan, then removes its paths.
+                    if let Err(e) = uninstaller.uninstall(&SystemRunner, &SystemProcesses, *force, dry_run) {
+                        // If uninstallation fails, log a warning with the error.
+                        log_warn!("Failed to uninstall {} '{}': {}", kind, uninstaller.name().bright_yellow(), e.to_string().bright_white());
+                    } else if !dry_run {
+                        // If uninstallation succeeds, log a success message.
+                        log_info!("Successfully uninstalled {} '{}'", kind, uninstaller.name().bright_green());
+                    }
+                }
             }
         }
 
@@ -84,6 +111,38 @@ fn main() {
 
             }
         }
+        Commands::Duplicates { dirs, min_size, keep, prefer } => {
+            // Fall back to the same user directories the `LargeFilesCleaner` walks.
+            let roots = if dirs.is_empty() { common_user_dirs() } else { dirs.clone() };
+            if let Err(e) = run_duplicates(&roots, *min_size, *keep, prefer.as_deref(), dry_run) {
+                log_error!("{}: {}", "Duplicate clean-up failed".bright_yellow(), e.to_string().bright_red());
+            }
+        }
+        Commands::IosBackups { delete } => {
+            if let Err(e) = run_ios_backups(delete, dry_run) {
+                log_error!("{}: {}", "iOS backup clean-up failed".bright_yellow(), e.to_string().bright_red());
+            }
+        }
+        Commands::ThinBinaries { apps, strip } => {
+            if let Err(e) = run_thin_binaries(&SystemRunner, apps, *strip, dry_run) {
+                log_error!("{}: {}", "Binary thinning failed".bright_yellow(), e.to_string().bright_red());
+            }
+        }
+        Commands::Snapshots { volume, delete, delete_all, thin } => {
+            if let Err(e) = run_snapshots(&SystemRunner, volume, delete, *delete_all, *thin, dry_run) {
+                log_error!("{}: {}", "Snapshot clean-up failed".bright_yellow(), e.to_string().bright_red());
+            }
+        }
+        Commands::Toolchains { prune } => {
+            if let Err(e) = run_toolchains(*prune, dry_run) {
+                log_error!("{}: {}", "Toolchain pruning failed".bright_yellow(), e.to_string().bright_red());
+            }
+        }
+        Commands::Apps { command: AppsCommand::List { sort, json } } => {
+            if let Err(e) = run_apps_list(*sort, *json) {
+                log_error!("{}: {}", "Listing apps failed".bright_yellow(), e.to_string().bright_red());
+            }
+        }
         Commands::Version => {
             log_debug!("{}","[main] 'Version' subcommand detected. Calling version::run().".bold());
             version::run();
@@ -1,5 +1,8 @@
 use clap::{Parser, Subcommand}; // Import `Parser` and `Subcommand` traits/macros from the `clap` crate.
 // `clap` is a popular Rust library for parsing command-line arguments.
+use crate::core::apps::AppSort;
+use crate::core::duplicates::KeepStrategy;
+use std::path::PathBuf;
 
 /// Command-line interface for the `osx` utility.
 ///
@@ -50,6 +53,22 @@ pub enum Commands {
         /// This field captures the positional argument `name` for the `uninstall` subcommand.
         /// When a user types `osx uninstall MyCoolApp`, "MyCoolApp" will be stored in `name`.
         name: String, // The name of the application or tool to be uninstalled.
+
+        /// Print the uninstall plans (paths, categories, sizes) as JSON, without removing anything
+        #[arg(long)]
+        json: bool,
+
+        /// After uninstalling a Homebrew-managed tool or cask, run `brew autoremove` to remove unused dependencies
+        #[arg(long)]
+        autoremove: bool,
+
+        /// Terminate the app or tool if it is running (SIGTERM, then SIGKILL) instead of aborting
+        #[arg(long)]
+        force: bool,
+
+        /// Uninstall the CLI tool `name` without looking for an app (e.g. `go` when GoLand is installed)
+        #[arg(long)]
+        tool: bool,
     },
 
     /// Clean junk files from system locations
@@ -67,6 +86,89 @@ pub enum Commands {
         #[arg(long, short, value_delimiter = ',')]
         ignore: Vec<String>, // A vector of strings, where each string is a path to be ignored.
     },
+    /// Find duplicate files and optionally keep only one copy per set
+    ///
+    /// This variant corresponds to the `duplicates` subcommand.
+    Duplicates {
+        /// Directories to scan (defaults to Downloads, Desktop, Documents, Movies, Music and Pictures)
+        dirs: Vec<PathBuf>,
+
+        /// Ignore files smaller than this many bytes
+        #[arg(long, default_value_t = 1)]
+        min_size: u64,
+
+        /// Keep one copy per set and delete the others, choosing the copy to keep by this strategy
+        #[arg(long, value_enum)]
+        keep: Option<KeepStrategy>,
+
+        /// Directory whose copies are kept when using `--keep path`
+        #[arg(long, required_if_eq("keep", "path"))]
+        prefer: Option<PathBuf>,
+    },
+    /// List local iOS device backups and optionally delete some of them
+    ///
+    /// This variant corresponds to the `ios-backups` subcommand.
+    IosBackups {
+        /// Ids (folder names) of backups to delete, separated by commas
+        #[arg(long, value_delimiter = ',')]
+        delete: Vec<String>,
+    },
+    /// Report (and optionally strip) architecture slices of universal binaries that don't match this Mac
+    ///
+    /// This variant corresponds to the `thin-binaries` subcommand.
+    ThinBinaries {
+        /// App bundles to inspect (defaults to every app in /Applications)
+        apps: Vec<PathBuf>,
+        /// Remove the slices for other architectures (the binaries are rewritten in place)
+        #[arg(long)]
+        strip: bool,
+    },
+    /// List local Time Machine snapshots, and delete or thin them
+    ///
+    /// This variant corresponds to the `snapshots` subcommand.
+    Snapshots {
+        /// Volume whose snapshots are managed
+        #[arg(long, default_value = "/")]
+        volume: String,
+        /// Dates of snapshots to delete (e.g. 2024-05-01-102030), separated by commas
+        #[arg(long, value_delimiter = ',', conflicts_with = "delete_all")]
+        delete: Vec<String>,
+        /// Delete every local snapshot of the volume
+        #[arg(long)]
+        delete_all: bool,
+        /// Ask macOS to purge this many bytes of snapshot data
+        #[arg(long, value_name = "BYTES")]
+        thin: Option<u64>,
+    },
+    /// List installed toolchain versions (rustup, nvm, pyenv, rbenv, sdkman) and prune unused ones
+    ///
+    /// This variant corresponds to the `toolchains` subcommand.
+    Toolchains {
+        /// Remove every version that is neither a default nor pinned by a project
+        #[arg(long)]
+        prune: bool,
+    },
+    /// Inspect installed applications
+    ///
+    /// This variant corresponds to the `apps` subcommand, whose actions are defined by `AppsCommand`.
+    Apps {
+        #[command(subcommand)]
+        command: AppsCommand,
+    },
     /// Shows the version of the tool and compare if newer version is available
     Version,
+}
+
+/// Actions of the `apps` subcommand.
+#[derive(Subcommand)]
+pub enum AppsCommand {
+    /// List installed apps with their version, bundle ID, size, data size and last use
+    List {
+        /// Order of the list
+        #[arg(long, value_enum, default_value_t = AppSort::Footprint)]
+        sort: AppSort,
+        /// Print the list as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
\ No newline at end of file
@@ -0,0 +1,202 @@
+// This file implements the `apps list` subcommand: an inventory of the installed applications
+// with their footprint (bundle size plus the size of the data the `MacApp` uninstaller would remove),
+// so the apps worth removing can be spotted before running `osx uninstall`.
+
+use crate::core::cleaners::{format_age, format_bytes};
+use crate::core::installed_apps::read_app;
+use crate::core::uninstaller::{MacApp, Uninstaller, expand_path_patterns};
+use crate::log_info;
+use crate::utils::filesystem::calculate_dir_size;
+use clap::ValueEnum;
+use colored::Colorize;
+use glob::glob;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+use tabled::{Table, Tabled, settings::Style};
+
+/// How the `apps list` inventory is sorted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AppSort {
+    /// Largest total footprint (bundle and data) first.
+    Footprint,
+    /// Least recently used first.
+    LastUsed,
+    /// Alphabetically by name.
+    Name,
+}
+
+/// An installed application and the disk space it uses.
+#[derive(Clone, Debug, Serialize)]
+pub struct AppFootprint {
+    pub name: String,
+    pub version: Option<String>,
+    pub bundle_id: Option<String>,
+    pub path: PathBuf,
+    /// Size of the `.app` bundle, in bytes.
+    pub bundle_size: u64,
+    /// Size of the related data found by the `MacApp` uninstaller (preferences, caches, containers...), in bytes.
+    pub data_size: u64,
+    /// `bundle_size` + `data_size`, in bytes.
+    pub total_size: u64,
+    /// Last time the app's executable was accessed, in seconds since the Unix epoch.
+    pub last_used: Option<u64>,
+    /// Existing related data paths, as removed by `osx uninstall`.
+    pub data_paths: Vec<PathBuf>,
+}
+
+/// Represents a row of the apps table.
+#[derive(Tabled)]
+struct AppEntry {
+    #[tabled(rename = "App")]
+    name: String,
+    #[tabled(rename = "Version")]
+    version: String,
+    #[tabled(rename = "Bundle ID")]
+    bundle_id: String,
+    #[tabled(rename = "Bundle")]
+    bundle_size: String,
+    #[tabled(rename = "Data")]
+    data_size: String,
+    #[tabled(rename = "Total")]
+    total_size: String,
+    #[tabled(rename = "Last Used")]
+    last_used: String,
+}
+
+/// Returns the directories listed by `apps list`: the apps a user can remove.
+pub fn listed_app_dirs() -> Vec<PathBuf> {
+    let home = env::var("HOME").unwrap_or_default();
+    vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from("/Applications/Utilities"),
+        PathBuf::from(format!("{}/Applications", home)),
+    ]
+}
+
+/// Computes the footprint of the app bundle at `path`, with the data it keeps in the home folder `home`.
+///
+/// The data size covers the existing paths returned by `MacApp::find_related_paths`, except the bundle itself.
+/// The last use is the access time of the bundle's main executable.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::apps::app_footprint;
+/// use std::{env, fs};
+///
+/// let home = env::temp_dir().join(format!("osx-doctest-app-footprint-{}", std::process::id()));
+/// let bundle = home.join("Applications/Sketchpad.app");
+/// fs::create_dir_all(bundle.join("Contents/MacOS")).unwrap();
+/// fs::write(
+///     bundle.join("Contents/Info.plist"),
+///     r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <plist version="1.0"><dict>
+///     <key>CFBundleIdentifier</key><string>com.example.sketchpad</string>
+///     <key>CFBundleExecutable</key><string>Sketchpad</string>
+///     <key>CFBundleShortVersionString</key><string>2.1</string>
+/// </dict></plist>"#,
+/// )
+/// .unwrap();
+/// fs::write(bundle.join("Contents/MacOS/Sketchpad"), vec![0u8; 1000]).unwrap();
+/// let caches = home.join("Library/Caches/com.example.sketchpad");
+/// fs::create_dir_all(&caches).unwrap();
+/// fs::write(caches.join("Cache.db"), vec![0u8; 500]).unwrap();
+///
+/// let app = app_footprint(&bundle, &home);
+/// assert_eq!(app.name, "Sketchpad");
+/// assert_eq!(app.version.as_deref(), Some("2.1"));
+/// assert_eq!(app.data_paths, vec![caches]);
+/// assert_eq!(app.data_size, 500);
+/// assert_eq!(app.total_size, app.bundle_size + 500);
+/// assert!(app.last_used.is_some());
+/// ```
+pub fn app_footprint(path: &Path, home: &Path) -> AppFootprint {
+    let info = read_app(path);
+    let data_paths: Vec<PathBuf> = expand_path_patterns(MacApp::from_bundle(path).with_home(home).find_related_paths())
+        .into_iter()
+        .filter(|p| p != path && p.exists())
+        .collect();
+    let bundle_size = calculate_dir_size(path).unwrap_or(0);
+    let data_size = data_paths.iter().map(|p| calculate_dir_size(p).unwrap_or(0)).sum();
+
+    // Launching an app reads its executable, so its access time tells when the app was last used.
+    let executable = path.join("Contents/MacOS").join(info.executable.as_deref().unwrap_or(&info.name));
+    let last_used = fs::metadata(&executable)
+        .and_then(|m| m.accessed())
+        .or_else(|_| fs::metadata(path).and_then(|m| m.modified()))
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    AppFootprint {
+        name: info.name,
+        version: info.version,
+        bundle_id: info.bundle_id,
+        path: path.to_path_buf(),
+        bundle_size,
+        data_size,
+        total_size: bundle_size + data_size,
+        last_used,
+        data_paths,
+    }
+}
+
+/// Lists the apps of `listed_app_dirs()` with their footprint, sorted by `sort`.
+pub fn list_apps(sort: AppSort) -> Vec<AppFootprint> {
+    let bundles: Vec<PathBuf> = listed_app_dirs()
+        .iter()
+        .filter_map(|dir| glob(&format!("{}/*.app", glob::Pattern::escape(&dir.to_string_lossy()))).ok())
+        .flat_map(|paths| paths.flatten())
+        .collect();
+
+    let home = PathBuf::from(env::var("HOME").unwrap_or_default());
+    let mut apps: Vec<AppFootprint> = bundles.par_iter().map(|path| app_footprint(path, &home)).collect();
+    match sort {
+        AppSort::Footprint => apps.sort_by_key(|app| std::cmp::Reverse(app.total_size)),
+        AppSort::LastUsed => apps.sort_by_key(|app| app.last_used.unwrap_or(0)),
+        AppSort::Name => apps.sort_by_key(|app| app.name.to_lowercase()),
+    }
+    apps
+}
+
+/// Runs the `apps list` subcommand, printing a table, or JSON with `json`.
+pub fn run_apps_list(sort: AppSort, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let apps = list_apps(sort);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&apps)?);
+        return Ok(());
+    }
+    if apps.is_empty() {
+        log_info!("{}", "No applications found.".bright_white());
+        return Ok(());
+    }
+
+    let entries: Vec<AppEntry> = apps
+        .iter()
+        .map(|app| AppEntry {
+            name: app.name.clone(),
+            version: app.version.clone().unwrap_or_default(),
+            bundle_id: app.bundle_id.clone().unwrap_or_default(),
+            bundle_size: format_bytes(app.bundle_size),
+            data_size: format_bytes(app.data_size),
+            total_size: format_bytes(app.total_size),
+            last_used: format_age(app.last_used.map(|secs| UNIX_EPOCH + Duration::from_secs(secs))),
+        })
+        .collect();
+
+    println!("\n{}\n", "📦 Installed Applications".bold().underline().purple());
+    println!("{}", Table::new(&entries).with(Style::modern()));
+    eprintln!();
+    log_info!(
+        "🧠 {} apps using {} in total. Remove one with {}",
+        apps.len(),
+        format_bytes(apps.iter().map(|app| app.total_size).sum()).bright_green().bold(),
+        "osx uninstall <name>".bright_white()
+    );
+    Ok(())
+}
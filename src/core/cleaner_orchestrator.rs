@@ -3,47 +3,46 @@ use crate::utils::filesystem::split_filenames;
 use crate::{log_debug, log_info, log_warn};
 use colored::Colorize;
 use rayon::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     env,
     sync::atomic::AtomicU64,
     sync::{Arc, Mutex},
 };
 use tabled::{Table, settings::Style};
 // Import the Cleaner trait and all specific cleaner implementations
+use super::cleaners::log_rotation::log_rotation_enabled;
+use super::time_machine::explain_purgeable_space;
+use crate::utils::command::SystemRunner;
 use super::cleaners::{
-    BrowserCachesCleaner, Cleaner, CleanupEntry, CrashReporterLogsCleaner, FailedEntry,
-    LargeFilesCleaner, PathToCheck, SkippedEntry, SystemCachesCleaner, TemporaryFilesCleaner,
-    TrashCleaner, UserCachesCleaner, UserLogsCleaner, format_bytes, is_sip_enabled,
+    BrokenSymlinksCleaner, BrowserCachesCleaner, Cleaner, CleanupEntry, CrashReporterLogsCleaner, DuplicateFilesCleaner,
+    ElectronAppCachesCleaner, FailedEntry, FinderMetadataCleaner, IdeCachesCleaner, InstallerLeftoversCleaner, IosBackupsCleaner, LanguageFilesCleaner, LargeFilesCleaner, LogRotationCleaner, MailDownloadsCleaner,
+    MessagesAttachmentsCleaner, OrphanedAppDataCleaner, PathToCheck, SkippedEntry, SystemCachesCleaner, TemporaryFilesCleaner,
+    ToolchainsCleaner, TrashCleaner, UniversalBinariesCleaner, UserCachesCleaner, UserLogsCleaner, VmDiskImagesCleaner, format_bytes, is_sip_enabled,
 };
 
 // Helper function to update the aggregated log maps
-// This function takes a mutable reference to an Arc<Mutex<HashMap<String, u64>>>
+// This function takes a mutable reference to an Arc<Mutex<HashMap<String, (u64, u64)>>>
 // This allows it to update shared, thread-safe hash maps from multiple threads.
+// Each value holds the total size in bytes and the number of paths aggregated under the key.
 fn update_aggregated_log_map(
-    log_map: &Arc<Mutex<HashMap<String, u64>>>,
+    log_map: &Arc<Mutex<HashMap<String, (u64, u64)>>>,
     cleaner_name: &str,
-    path: &PathBuf,
+    summary_path: &Path,
     size: u64,
 ) {
-    // Determine the common display path for aggregation.
-    // If it's a file within a directory, the parent directory is used for aggregation.
-    // Otherwise, the path itself is used. This helps group related files under a common entry.
-    let common_display_path = if path.components().count() > 1 && path.file_name().is_some() {
-        // For files within a directory, log the parent directory for aggregation
-        path.parent().unwrap_or(path).display().to_string()
-    } else {
-        // For top-level directories (which cleaners often return), log the path itself
-        path.display().to_string()
-    };
-
     // Create a unique key for the HashMap entry, combining cleaner name and the common path.
-    let entry_key = format!("{}: {}", cleaner_name, common_display_path);
-    // Acquire a lock on the log map, then update the size for the corresponding entry.
+    // The common path is chosen by the cleaner (see `Cleaner::summary_path`) so related files
+    // are grouped under a single entry.
+    let entry_key = format!("{}: {}", cleaner_name, summary_path.display());
+    // Acquire a lock on the log map, then update the size and count for the corresponding entry.
     // If the entry doesn't exist, it's inserted with the current size; otherwise, the size is added.
-    *log_map.lock().unwrap().entry(entry_key).or_insert(0) += size;
+    let mut log_map_locked = log_map.lock().unwrap();
+    let (total_size, item_count) = log_map_locked.entry(entry_key).or_insert((0, 0));
+    *total_size += size;
+    *item_count += 1;
 }
 
 /// The main function for cleaning macOS system junk and temporary files.
@@ -63,19 +62,38 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
 
     // Initialize a vector of `Cleaner` trait objects. These are the standard cleaners
     // that target common junk files like caches, temporary files, logs, and trash.
-    let standard_cleaners: Vec<Box<dyn Cleaner>> = vec![
+    let mut standard_cleaners: Vec<Box<dyn Cleaner>> = vec![
         Box::new(SystemCachesCleaner::new()),
         Box::new(UserCachesCleaner::new()),
         Box::new(TemporaryFilesCleaner::new()),
-        Box::new(UserLogsCleaner::new()),
-        Box::new(CrashReporterLogsCleaner::new()),
         Box::new(TrashCleaner::new()),
         Box::new(BrowserCachesCleaner::new()),
+        Box::new(ElectronAppCachesCleaner::new()),
+        Box::new(DuplicateFilesCleaner::new()),
+        Box::new(FinderMetadataCleaner::new()),
+        Box::new(BrokenSymlinksCleaner::new()),
+        Box::new(MailDownloadsCleaner::new()),
+        Box::new(MessagesAttachmentsCleaner::new()),
+        Box::new(IosBackupsCleaner::new()),
+        Box::new(LanguageFilesCleaner::new()),
+        Box::new(LanguageFilesCleaner::for_signed_apps()),
+        Box::new(UniversalBinariesCleaner::new()),
+        Box::new(OrphanedAppDataCleaner::new()),
+        Box::new(VmDiskImagesCleaner::new()),
+        Box::new(IdeCachesCleaner::new()),
+        Box::new(ToolchainsCleaner::new()),
     ];
+    // Logs are rotated by default; `OSX_LOG_MODE=delete` restores deleting the log folders as a whole.
+    if log_rotation_enabled() {
+        standard_cleaners.push(Box::new(LogRotationCleaner::new()));
+    } else {
+        standard_cleaners.push(Box::new(UserLogsCleaner::new()));
+        standard_cleaners.push(Box::new(CrashReporterLogsCleaner::new()));
+    }
 
     // Shared accumulators for logs and results across all parallel cleaners.
     // `Arc<Mutex<T>>` is used to allow safe shared access and mutation from multiple threads.
-    let all_successful_entries_map: Arc<Mutex<HashMap<String, u64>>> =
+    let all_successful_entries_map: Arc<Mutex<HashMap<String, (u64, u64)>>> =
         Arc::new(Mutex::new(HashMap::new()));
     let all_failed_entries: Arc<Mutex<Vec<FailedEntry>>> = Arc::new(Mutex::new(Vec::new()));
     let all_skipped_during_size_check: Arc<Mutex<Vec<SkippedEntry>>> =
@@ -84,19 +102,22 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
 
     // Shared accumulators for categorized logs. These HashMaps will store aggregated
     // information (e.g., total size for a given cleaner and path category).
-    let checking_logs_map: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
-    let cleaning_logs_map: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
+    let checking_logs_map: Arc<Mutex<HashMap<String, (u64, u64)>>> = Arc::new(Mutex::new(HashMap::new()));
+    let cleaning_logs_map: Arc<Mutex<HashMap<String, (u64, u64)>>> = Arc::new(Mutex::new(HashMap::new()));
 
     // This vector will store all paths identified for potential cleaning after their size has been checked.
     // It's wrapped in `Arc<Mutex>` because it will be populated by parallel threads.
     let all_paths_to_clean_after_check: Arc<Mutex<Vec<PathToCheck>>> =
         Arc::new(Mutex::new(Vec::new()));
 
-    // New accumulator specifically for large files when in dry run mode.
-    // This HashMap will store aggregated information about large files to be displayed.
-    let large_files_to_display_in_dry_run_map: Arc<Mutex<HashMap<String, u64>>> =
+    // New accumulator specifically for large files and installers when in dry run mode.
+    // This HashMap will store aggregated information about these user files to be displayed.
+    let user_files_to_display_in_dry_run_map: Arc<Mutex<HashMap<String, (u64, u64)>>> =
         Arc::new(Mutex::new(HashMap::new()));
 
+    // Accumulator for paths found by report-only cleaners. These are displayed but never cleaned.
+    let reported_only_map: Arc<Mutex<HashMap<String, (u64, u64)>>> = Arc::new(Mutex::new(HashMap::new()));
+
     // Phase 1: Collect all paths to check and log "Checking"
     // This phase identifies files and directories that are candidates for cleaning.
     eprintln!("\n{}", "🔍 Verifying Paths...".bold().underline().cyan());
@@ -118,14 +139,26 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
 
                 for p in paths_found_by_cleaner {
                     // Calculate the size of each found path.
-                    match crate::utils::filesystem::calculate_dir_size(&p.path) {
+                    match cleaner.path_size(&p.path) {
                         Ok(size) => {
+                            // Report-only cleaners never hand their paths to `remove_path`;
+                            // they are listed in their own table instead.
+                            if cleaner.is_report_only() {
+                                update_aggregated_log_map(
+                                    &reported_only_map,
+                                    cleaner.name(),
+                                    &p.summary_path,
+                                    size,
+                                );
+                                continue;
+                            }
+
                             let formatted_size = crate::utils::filesystem::bytes_to_human(size);
                             // Use the helper function to update the aggregated "checking" logs.
                             update_aggregated_log_map(
                                 &checking_logs_map,
                                 cleaner.name(),
-                                &p.path,
+                                &p.summary_path,
                                 size,
                             );
 
@@ -135,6 +168,7 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
                                 initial_size: size,
                                 formatted_size,
                                 cleaner_name: cleaner.name().to_string(),
+                                summary_path: p.summary_path,
                             });
                         }
                         Err(e) => {
@@ -175,63 +209,88 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
         }
     });
 
-    // Special handling for `LargeFilesCleaner` based on `dry_run` mode.
-    // Large files are typically not removed by default unless explicitly configured.
-    let large_files_cleaner_instance = LargeFilesCleaner::new();
-    match large_files_cleaner_instance.clean(
-        &Arc::new(Mutex::new(Vec::new())),
-        &all_skipped_during_size_check,
-        &ignore,
-    ) {
-        Ok(paths_found_by_large_cleaner) => {
-            if dry_run {
-                // If in dry run, aggregate these large files into a separate map for display only.
-                for p in paths_found_by_large_cleaner {
-                    match crate::utils::filesystem::calculate_dir_size(&p.path) {
-                        Ok(size) => {
-                            // Update the map specifically for large files in dry run.
-                            update_aggregated_log_map(
-                                &large_files_to_display_in_dry_run_map,
-                                &p.cleaner_name,
-                                &p.path,
-                                size,
-                            );
-                        }
-                        Err(e) => {
-                            // Log warning if size of a large file cannot be determined.
-                            log_warn!(
-                                "⚠️ Could not determine size for large file path {}: {}",
-                                p.path.display(),
-                                e
-                            );
-                            all_skipped_during_size_check
-                                .lock()
-                                .unwrap()
-                                .push(SkippedEntry {
-                                    path: p.path.display().to_string(),
-                                    reason: format!(
-                                        "Could not determine size or access for large file: {}",
-                                        e
-                                    ),
-                                });
+    // Special handling for cleaners that target the user's own files (`LargeFilesCleaner`,
+    // `InstallerLeftoversCleaner`) based on `dry_run` mode.
+    // These files are only listed in their own table during a dry run; those of report-only
+    // cleaners (installers) are listed in the report-only table and never removed.
+    let user_file_cleaners: Vec<Box<dyn Cleaner>> = vec![
+        Box::new(LargeFilesCleaner::new()),
+        Box::new(InstallerLeftoversCleaner::new()),
+    ];
+    // A large installer is found by both cleaners; only the first one reports it.
+    let mut seen_user_files: HashSet<PathBuf> = HashSet::new();
+    for user_file_cleaner in &user_file_cleaners {
+        match user_file_cleaner.clean(
+            &Arc::new(Mutex::new(Vec::new())),
+            &all_skipped_during_size_check,
+            &ignore,
+        ) {
+            Ok(paths_found_by_cleaner) => {
+                let paths_found_by_cleaner: Vec<PathToCheck> = paths_found_by_cleaner
+                    .into_iter()
+                    .filter(|p| seen_user_files.insert(p.path.clone()))
+                    .collect();
+                let report_only = user_file_cleaner.is_report_only();
+                if dry_run || report_only {
+                    // If in dry run, aggregate these files into a separate map for display only.
+                    let display_map = if report_only {
+                        &reported_only_map
+                    } else {
+                        &user_files_to_display_in_dry_run_map
+                    };
+                    for p in paths_found_by_cleaner {
+                        match user_file_cleaner.path_size(&p.path) {
+                            Ok(size) => {
+                                // Update the map specifically for user files in dry run.
+                                update_aggregated_log_map(
+                                    display_map,
+                                    &p.cleaner_name,
+                                    &p.summary_path,
+                                    size,
+                                );
+                            }
+                            Err(e) => {
+                                // Log warning if size of a user file cannot be determined.
+                                log_warn!(
+                                    "⚠️ Could not determine size for {} path {}: {}",
+                                    user_file_cleaner.name(),
+                                    p.path.display(),
+                                    e
+                                );
+                                all_skipped_during_size_check
+                                    .lock()
+                                    .unwrap()
+                                    .push(SkippedEntry {
+                                        path: p.path.display().to_string(),
+                                        reason: format!(
+                                            "Could not determine size or access for {}: {}",
+                                            user_file_cleaner.name(),
+                                            e
+                                        ),
+                                    });
+                            }
                         }
                     }
+                } else {
+                    // If not dry run, these files are added to the main list for actual cleaning.
+                    all_paths_to_clean_after_check
+                        .lock()
+                        .unwrap()
+                        .extend(paths_found_by_cleaner);
                 }
-            } else {
-                // If not dry run, these large files are added to the main list for actual cleaning.
-                all_paths_to_clean_after_check
-                    .lock()
-                    .unwrap()
-                    .extend(paths_found_by_large_cleaner);
             }
-        }
-        Err(e) => {
-            // Log warning if the Large Files Cleaner fails.
-            log_warn!("❌ Large Files Cleaner failed to identify paths: {}", e);
-            all_failed_entries.lock().unwrap().push(FailedEntry {
-                path: "Large Files Cleaner".to_string(),
-                error: format!("Failed to run: {}", e),
-            });
+            Err(e) => {
+                // Log warning if the cleaner fails.
+                log_warn!(
+                    "❌ {} Cleaner failed to identify paths: {}",
+                    user_file_cleaner.name(),
+                    e
+                );
+                all_failed_entries.lock().unwrap().push(FailedEntry {
+                    path: format!("{} Cleaner", user_file_cleaner.name()),
+                    error: format!("Failed to run: {}", e),
+                });
+            }
         }
     }
 
@@ -242,7 +301,7 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
         // Sort logs by key (cleaner name/path) for consistent output.
         sorted_logs.sort_by_key(|&(k, _)| k);
 
-        for (key, &total_size) in sorted_logs {
+        for (key, &(total_size, _)) in sorted_logs {
             let (cleaner_name_only, path_only) = split_filenames(key);
             log_info!(
                 "🔍 Checking: '{}' {} ({})",
@@ -266,28 +325,38 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
     // Acquire lock once to get a reference to the inner Vec, then use `par_iter` on that reference.
     // This avoids repeatedly locking and unlocking the mutex for each item.
     let paths_to_process = all_paths_to_clean_after_check.lock().unwrap();
+    // Each path is cleaned by the cleaner that found it, so cleaners can rewrite files instead of deleting them.
+    let cleaners_by_name: HashMap<&str, &dyn Cleaner> = standard_cleaners
+        .iter()
+        .chain(user_file_cleaners.iter())
+        .map(|cleaner| (cleaner.name(), cleaner.as_ref()))
+        .collect();
     paths_to_process.par_iter().for_each(|p| {
         let path_display = p.path.display().to_string();
-        // Attempt to remove the path. `dry_run` controls actual deletion.
-        match crate::utils::filesystem::remove_path(&p.path, dry_run) {
-            Ok(_) => {
+        // Attempt to clean the path. `dry_run` controls actual deletion.
+        let result = match cleaners_by_name.get(p.cleaner_name.as_str()) {
+            Some(cleaner) => cleaner.clean_path(&p.path, p.initial_size, dry_run),
+            None => crate::utils::filesystem::remove_path(&p.path, dry_run).map(|_| p.initial_size),
+        };
+        match result {
+            Ok(freed) => {
                 // Use the helper function to update the aggregated "cleaning" logs.
                 update_aggregated_log_map(
                     &cleaning_logs_map,
                     &p.cleaner_name,
-                    &p.path,
-                    p.initial_size,
+                    &p.summary_path,
+                    freed,
                 );
 
                 // Update the `all_successful_entries_map` for the final summary table.
                 update_aggregated_log_map(
                     &all_successful_entries_map,
                     &p.cleaner_name,
-                    &p.path,
-                    p.initial_size,
+                    &p.summary_path,
+                    freed,
                 );
                 // Atomically add the cleaned size to the total freed space.
-                total_freed_space.fetch_add(p.initial_size, Ordering::SeqCst);
+                total_freed_space.fetch_add(freed, Ordering::SeqCst);
             }
             Err(e) => {
                 // Log a warning if cleaning fails for a specific path.
@@ -315,7 +384,7 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
         } else {
             eprintln!("\n{}", "☑️  Reclaimed Space...\n".bold().underline().cyan());
         }
-        for (key, &total_size) in sorted_logs {
+        for (key, &(total_size, _)) in sorted_logs {
             let (cleaner_name_only, path_only) = split_filenames(key);
             if dry_run {
                 log_info!(
@@ -346,20 +415,24 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
     let mut sorted_aggregated_success: Vec<_> = aggregated_success_map.iter().collect();
     sorted_aggregated_success.sort_by_key(|&(k, _)| k); // Sort for consistent order.
 
-    for (key, &total_size) in sorted_aggregated_success {
+    let mut total_item_count = 0;
+    for (key, &(total_size, item_count)) in sorted_aggregated_success {
         // Split the aggregated key back into cleaner name and path for table display.
         let (cleaner_name_only, path_only) = split_filenames(key);
 
         final_successful_entries.push(CleanupEntry {
             path: path_only,
+            items: item_count.to_string(),
             size: format_bytes(total_size),
             cleaner_name: cleaner_name_only,
         });
+        total_item_count += item_count;
     }
 
     // Add a total row to the successful cleanup entries.
     final_successful_entries.push(CleanupEntry {
         path: "Total".to_string(),
+        items: total_item_count.to_string(),
         size: total_fmt.clone(),
         cleaner_name: "".to_string(),
     });
@@ -398,32 +471,36 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
         println!("{}", table);
     }
 
-    // Display Large Files table if in dry run mode and files were found.
+    // Display Large Files & Installers table if in dry run mode and files were found.
     if dry_run {
         let large_files_for_display_map_locked =
-            large_files_to_display_in_dry_run_map.lock().unwrap();
+            user_files_to_display_in_dry_run_map.lock().unwrap();
         if !large_files_for_display_map_locked.is_empty() {
             eprintln!("\n");
             let mut display_entries: Vec<CleanupEntry> = Vec::new();
             let mut total_large_file_size = 0;
+            let mut total_large_file_count = 0;
 
             let mut sorted_large_files: Vec<_> =
                 large_files_for_display_map_locked.iter().collect();
             sorted_large_files.sort_by_key(|&(k, _)| k);
 
-            for (key, &total_size) in sorted_large_files {
+            for (key, &(total_size, item_count)) in sorted_large_files {
                 let (cleaner_name_only, path_only) = split_filenames(key);
 
                 display_entries.push(CleanupEntry {
                     path: path_only,
+                    items: item_count.to_string(),
                     size: format_bytes(total_size),
                     cleaner_name: cleaner_name_only,
                 });
                 total_large_file_size += total_size;
+                total_large_file_count += item_count;
             }
 
             display_entries.push(CleanupEntry {
-                path: "Total Large Files".to_string(),
+                path: "Total Large Files & Installers".to_string(),
+                items: total_large_file_count.to_string(),
                 size: format_bytes(total_large_file_size),
                 cleaner_name: "".to_string(),
             });
@@ -434,12 +511,57 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
 
             println!(
                 "{}",
-                "📦 Large Files Found (Dry Run)".bold().underline().blue()
+                "📦 Large Files & Installers Found (Dry Run)"
+                    .bold()
+                    .underline()
+                    .blue()
             );
             println!("{}", table);
         }
     }
 
+    // Display paths found by report-only cleaners, in both dry run and actual run.
+    let reported_only_map_locked = reported_only_map.lock().unwrap();
+    if !reported_only_map_locked.is_empty() {
+        eprintln!("\n");
+        let mut display_entries: Vec<CleanupEntry> = Vec::new();
+        let mut total_reported_size = 0;
+        let mut total_reported_count = 0;
+
+        let mut sorted_reported: Vec<_> = reported_only_map_locked.iter().collect();
+        sorted_reported.sort_by_key(|&(k, _)| k);
+
+        for (key, &(total_size, item_count)) in sorted_reported {
+            let (cleaner_name_only, path_only) = split_filenames(key);
+
+            display_entries.push(CleanupEntry {
+                path: path_only,
+                items: item_count.to_string(),
+                size: format_bytes(total_size),
+                cleaner_name: cleaner_name_only,
+            });
+            total_reported_size += total_size;
+            total_reported_count += item_count;
+        }
+
+        display_entries.push(CleanupEntry {
+            path: "Total Reported".to_string(),
+            items: total_reported_count.to_string(),
+            size: format_bytes(total_reported_size),
+            cleaner_name: "".to_string(),
+        });
+
+        let table = Table::new(&display_entries)
+            .with(Style::modern())
+            .to_string();
+
+        println!(
+            "{}",
+            "🔎 Reported Only (Not Cleaned)".bold().underline().blue()
+        );
+        println!("{}", table);
+    }
+
     // Display skipped paths during size check if the debug environment variable is set or debug logging is enabled.
     if env::var("OSX_SHOW_SKIPPED").is_ok() || is_debug_enabled() {
         let all_skipped = all_skipped_during_size_check.lock().unwrap();
@@ -468,6 +590,9 @@ pub fn clean_my_mac(dry_run: bool, ignore: Vec<String>) -> Result<(), Box<dyn st
         log_info!("✔ Total space freed: {}", total_fmt.bright_green().bold());
     }
 
+    // Local snapshots keep deleted files' blocks alive, so explain why Finder may not show the freed space yet.
+    explain_purgeable_space(&SystemRunner);
+
     // Warn the user if System Integrity Protection (SIP) is enabled, as it might limit cleaning.
     if is_sip_enabled() {
         log_info!(
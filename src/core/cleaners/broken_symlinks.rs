@@ -0,0 +1,112 @@
+use crate::core::cleaners::Cleaner;
+use crate::core::uninstaller::{BIN_DIRS, launch_agent_dirs};
+use crate::{log_debug, log_info};
+use colored::Colorize;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Represents a cleaner for dangling symlinks in binary and LaunchAgents directories.
+///
+/// These links are typical leftovers after `osx uninstall` (or a manual uninstall) removed
+/// a Homebrew Cellar directory or an app bundle, but not the links pointing into it.
+/// The missing target of every link is logged, so the user can see what it belonged to.
+pub struct BrokenSymlinksCleaner;
+
+impl BrokenSymlinksCleaner {
+    pub fn new() -> Self {
+        BrokenSymlinksCleaner // This simply returns an instance of the struct
+    }
+}
+
+impl Default for BrokenSymlinksCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for BrokenSymlinksCleaner {
+    fn name(&self) -> &str {
+        "Broken Symlinks"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let home = env::var("HOME").unwrap_or_default();
+        // The same directories the uninstaller searches for binaries and launch agents,
+        // plus the per-user binary directories.
+        let mut dirs: Vec<PathBuf> = BIN_DIRS.iter().map(PathBuf::from).collect();
+        dirs.push(PathBuf::from(format!("{}/bin", home)));
+        dirs.push(PathBuf::from(format!("{}/.local/bin", home)));
+        dirs.extend(launch_agent_dirs());
+
+        let mut paths = Vec::new();
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if let Some(target) = dangling_symlink_target(&path) {
+                    log_info!(
+                        "🔗 Broken symlink: {} -> {} {}",
+                        path.display().to_string().white().dimmed(),
+                        target.display().to_string().bright_white(),
+                        "(missing)".bright_yellow()
+                    );
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// Returns the (missing) target of `path` if it is a symlink whose target does not exist.
+/// Relative targets are resolved against the directory containing the link.
+///
+/// Links whose target can't be checked (e.g. an unreadable directory, or a symlink loop) are not
+/// reported: only a target that is definitely missing makes a link dangling.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::broken_symlinks::dangling_symlink_target;
+/// use std::{fs, os::unix::fs::symlink};
+///
+/// let dir = std::env::temp_dir().join(format!("osx-symlinks-doc-{}", std::process::id()));
+/// fs::create_dir_all(&dir).unwrap();
+/// fs::write(dir.join("tool"), "#!/bin/sh").unwrap();
+/// symlink(dir.join("tool"), dir.join("ok")).unwrap();
+/// symlink("../Cellar/gone/1.0/bin/gone", dir.join("gone")).unwrap();
+/// symlink(dir.join("loop"), dir.join("loop")).unwrap();
+///
+/// assert_eq!(dangling_symlink_target(&dir.join("ok")), None);
+/// assert_eq!(dangling_symlink_target(&dir.join("tool")), None);
+/// assert_eq!(dangling_symlink_target(&dir.join("loop")), None);
+/// assert_eq!(
+///     dangling_symlink_target(&dir.join("gone")),
+///     Some(dir.join("../Cellar/gone/1.0/bin/gone"))
+/// );
+/// fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn dangling_symlink_target(path: &Path) -> Option<PathBuf> {
+    let is_symlink = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    if !is_symlink {
+        return None;
+    }
+    // `metadata` follows the link; it also fails on permission errors and loops, which don't mean the target is gone.
+    match fs::metadata(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => {
+            log_debug!("Could not check the target of {}: {}", path.display(), e);
+            return None;
+        }
+        Ok(_) => return None,
+    }
+    let target = fs::read_link(path).ok()?;
+    Some(match path.parent() {
+        Some(parent) if target.is_relative() => parent.join(target),
+        _ => target,
+    })
+}
@@ -0,0 +1,56 @@
+use super::Cleaner;
+use super::large_files::common_user_dirs;
+use crate::core::duplicates::{KeepStrategy, find_duplicates};
+use crate::utils::config::env_u64;
+use std::path::PathBuf;
+
+/// Environment variable holding the minimum size, in bytes, of files compared for duplicates.
+pub const DUPLICATES_MIN_SIZE_ENV: &str = "OSX_DUPLICATES_MIN_SIZE";
+/// Default minimum size of files compared for duplicates (1 MB), which keeps `clean-my-mac` fast.
+const DEFAULT_MIN_SIZE_BYTES: u64 = 1024 * 1024;
+
+/// Represents a report-only cleaner for duplicate files in common user directories.
+///
+/// For every set of identical files, all copies except the oldest one are reported,
+/// so the reported size is the space that `osx duplicates --keep oldest` would reclaim.
+pub struct DuplicateFilesCleaner {
+    min_size: u64,
+}
+
+impl DuplicateFilesCleaner {
+    pub fn new() -> Self {
+        DuplicateFilesCleaner {
+            min_size: env_u64(DUPLICATES_MIN_SIZE_ENV, DEFAULT_MIN_SIZE_BYTES),
+        }
+    }
+}
+
+impl Default for DuplicateFilesCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for DuplicateFilesCleaner {
+    fn name(&self) -> &str {
+        "Duplicate Files"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        find_duplicates(&common_user_dirs(), self.min_size)
+            .into_iter()
+            .flat_map(|set| {
+                let keeper = set.keeper_index(KeepStrategy::Oldest, None);
+                set.files
+                    .into_iter()
+                    .enumerate()
+                    .filter(move |(index, _)| *index != keeper)
+                    .map(|(_, file)| file.path)
+            })
+            .collect()
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+}
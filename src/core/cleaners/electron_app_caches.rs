@@ -0,0 +1,143 @@
+use crate::core::cleaners::Cleaner;
+use crate::utils::config::env_list;
+use crate::{log_debug, log_warn};
+use colored::Colorize;
+use glob::{glob, Pattern};
+use std::{env, path::PathBuf};
+
+/// Cache sub-directories that Chromium/Electron creates inside an app's data directory.
+/// Only cache folders are listed; `Local Storage`, `IndexedDB` and cookies hold user state and are left alone.
+const ELECTRON_CACHE_DIRS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "Service Worker/CacheStorage",
+    "Service Worker/ScriptCache",
+    // Apps with multiple workspaces/accounts (e.g. Slack) keep one Chromium partition per workspace.
+    "Partitions/*/Cache",
+    "Partitions/*/Code Cache",
+    "Partitions/*/GPUCache",
+    "Partitions/*/Service Worker/CacheStorage",
+    "Partitions/*/Service Worker/ScriptCache",
+];
+
+/// Environment variable holding extra app data directory names (relative to
+/// `~/Library/Application Support`) to treat as Electron apps, separated by commas.
+pub const ELECTRON_APPS_ENV: &str = "OSX_ELECTRON_APPS";
+
+/// An Electron (or otherwise Chromium-based) application whose caches should be cleaned.
+pub struct ElectronApp {
+    /// User-friendly application name, used for logging.
+    pub name: String,
+    /// Data directories of the app, relative to the user's home directory.
+    pub data_dirs: Vec<String>,
+    /// Cache directories relative to each data directory. Glob patterns are allowed.
+    pub cache_dirs: Vec<String>,
+}
+
+impl ElectronApp {
+    /// Creates an entry for an app storing its data in `~/Library/Application Support/<support_dir>`
+    /// with the standard Electron cache layout.
+    fn standard(name: &str, support_dirs: &[&str]) -> Self {
+        ElectronApp {
+            name: name.to_string(),
+            data_dirs: support_dirs
+                .iter()
+                .map(|dir| format!("Library/Application Support/{}", dir))
+                .collect(),
+            cache_dirs: ELECTRON_CACHE_DIRS.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+}
+
+/// Returns the built-in catalog of well-known Electron and chat apps.
+pub fn builtin_electron_apps() -> Vec<ElectronApp> {
+    // The Mac App Store build of Slack is sandboxed, so its data lives inside its container.
+    let mut slack = ElectronApp::standard("Slack", &["Slack"]);
+    slack.data_dirs.push(
+        "Library/Containers/com.tinyspeck.slackmacgap/Data/Library/Application Support/Slack".to_string(),
+    );
+
+    vec![
+        slack,
+        ElectronApp::standard("Discord", &["discord"]),
+        ElectronApp::standard("Microsoft Teams", &["Microsoft/Teams", "Microsoft Teams"]),
+        ElectronApp::standard("VS Code", &["Code"]),
+        ElectronApp::standard("Cursor", &["Cursor"]),
+        ElectronApp::standard("Notion", &["Notion"]),
+        ElectronApp::standard("Figma", &["Figma", "Figma/DesktopProfile/*"]),
+        // Spotify is not Electron-based, but keeps its offline audio cache in the same place.
+        ElectronApp {
+            name: "Spotify".to_string(),
+            data_dirs: vec!["Library/Application Support/Spotify".to_string()],
+            cache_dirs: vec!["PersistentCache".to_string()],
+        },
+    ]
+}
+
+/// Represents a cleaner for the Chromium caches of Electron apps such as Slack, Discord and VS Code.
+///
+/// These apps keep their caches under `~/Library/Application Support/<App>/`, which
+/// `UserCachesCleaner` never sees because it only targets `~/Library/Caches`.
+/// Extra apps can be added through the `OSX_ELECTRON_APPS` environment variable.
+pub struct ElectronAppCachesCleaner {
+    apps: Vec<ElectronApp>,
+}
+
+impl ElectronAppCachesCleaner {
+    /// Creates a cleaner using the built-in catalog plus any apps configured in `OSX_ELECTRON_APPS`.
+    pub fn new() -> Self {
+        let mut apps = builtin_electron_apps();
+        for dir in env_list(ELECTRON_APPS_ENV) {
+            log_debug!("Adding configured Electron app: {}", dir);
+            apps.push(ElectronApp::standard(&dir, &[dir.as_str()]));
+        }
+        ElectronAppCachesCleaner { apps }
+    }
+}
+
+impl Default for ElectronAppCachesCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for ElectronAppCachesCleaner {
+    fn name(&self) -> &str {
+        "Electron App Caches"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let home_dir = match env::home_dir() {
+            Some(path) => path,
+            None => {
+                log_warn!("Could not find home directory, skipping Electron app cache scan.");
+                return paths;
+            }
+        };
+        let home = Pattern::escape(&home_dir.display().to_string());
+
+        for app in &self.apps {
+            for data_dir in &app.data_dirs {
+                for cache_dir in &app.cache_dirs {
+                    let pattern_str = format!("{}/{}/{}", home, data_dir, cache_dir);
+                    match glob(&pattern_str) {
+                        Ok(entries) => {
+                            for entry in entries.filter_map(|e| e.ok()) {
+                                if entry.is_dir() && !paths.contains(&entry) {
+                                    log_debug!("Found {} cache directory: {}", app.name, entry.display());
+                                    paths.push(entry);
+                                }
+                            }
+                        }
+                        Err(_) => log_warn!("Invalid glob pattern for {}: {}", app.name, pattern_str),
+                    }
+                }
+            }
+        }
+
+        paths
+    }
+}
@@ -0,0 +1,131 @@
+use crate::core::cleaners::Cleaner;
+use crate::utils::config::env_list;
+use crate::{log_debug, log_warn};
+use colored::Colorize;
+use glob::glob;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Environment variable holding the roots to sweep for Finder metadata, separated by commas.
+/// Entries may be glob patterns, e.g. `/Volumes/*` for every mounted volume.
+pub const FINDER_METADATA_ROOTS_ENV: &str = "OSX_FINDER_METADATA_ROOTS";
+
+/// macOS bookkeeping directories on volume roots. Their contents are managed by the system
+/// (Spotlight, FSEvents, versions), so the sweep never descends into them.
+const SYSTEM_VOLUME_DIRS: &[&str] = &[
+    ".Spotlight-V100",
+    ".fseventsd",
+    ".DocumentRevisions-V100",
+    ".TemporaryItems",
+    ".Trashes",
+];
+
+/// Represents a cleaner for Finder metadata files: `.DS_Store`, AppleDouble `._*` files and `Icon\r`.
+///
+/// These files are mostly noise on external volumes and network shares (and in git repositories),
+/// where they are seen by non-Mac machines. Only the roots selected through the `OSX_FINDER_METADATA_ROOTS`
+/// environment variable are swept: nothing by default. On non-HFS volumes, `._*` files hold resource forks
+/// and extended attributes, so sweeping mounted volumes (`/Volumes/*`) must be asked for explicitly.
+pub struct FinderMetadataCleaner {
+    roots: Vec<PathBuf>,
+}
+
+impl FinderMetadataCleaner {
+    pub fn new() -> Self {
+        let mut roots = Vec::new();
+        for root in env_list(FINDER_METADATA_ROOTS_ENV) {
+            if !root.contains(['*', '?', '[']) {
+                roots.push(PathBuf::from(root));
+                continue;
+            }
+            let Ok(matches) = glob(&root) else {
+                log_warn!("Invalid Finder metadata root pattern: {}", root);
+                continue;
+            };
+            for path in matches.flatten() {
+                // The boot volume shows up as a symlink to `/`; sweeping it would walk the whole disk.
+                let is_symlink = fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink());
+                if is_symlink {
+                    log_debug!("Skipping symlinked root: {}", path.display());
+                } else {
+                    roots.push(path);
+                }
+            }
+        }
+        if roots.is_empty() {
+            log_debug!("No Finder metadata roots selected (set {})", FINDER_METADATA_ROOTS_ENV);
+        }
+        FinderMetadataCleaner { roots }
+    }
+}
+
+impl Default for FinderMetadataCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for FinderMetadataCleaner {
+    fn name(&self) -> &str {
+        "Finder Metadata"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for root in &self.roots {
+            if !root.is_dir() {
+                log_warn!("Finder metadata root is not a directory: {}", root.display());
+                continue;
+            }
+
+            let walker = WalkDir::new(root)
+                // Stay on the root's file system, so nested mounts are only swept when listed themselves.
+                .same_file_system(true)
+                .into_iter()
+                .filter_entry(|e| {
+                    !(e.file_type().is_dir()
+                        && e.file_name().to_str().is_some_and(|n| SYSTEM_VOLUME_DIRS.contains(&n)))
+                })
+                .filter_map(|e| e.ok());
+
+            for entry in walker {
+                if entry.file_type().is_file()
+                    && entry.file_name().to_str().is_some_and(is_finder_metadata_file)
+                {
+                    paths.push(entry.into_path());
+                }
+            }
+        }
+        paths
+    }
+
+    /// Finder metadata files are spread over every folder of a volume, so they are
+    /// summarized per sweep root instead of per parent directory.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        self.roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .cloned()
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+}
+
+/// Returns `true` if `file_name` is a Finder metadata file.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::finder_metadata::is_finder_metadata_file;
+/// assert!(is_finder_metadata_file(".DS_Store"));
+/// assert!(is_finder_metadata_file("._photo.jpg"));
+/// assert!(is_finder_metadata_file("Icon\r"));
+/// assert!(!is_finder_metadata_file("Icon"));
+/// assert!(!is_finder_metadata_file("photo.jpg"));
+/// ```
+pub fn is_finder_metadata_file(file_name: &str) -> bool {
+    file_name == ".DS_Store" || file_name == "Icon\r" || (file_name.starts_with("._") && file_name.len() > 2)
+}
@@ -0,0 +1,237 @@
+use crate::core::cleaners::Cleaner;
+use crate::{log_debug, log_info};
+use colored::Colorize;
+use plist::Value;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Vendor folders holding per-version IDE data, relative to `~/Library/{Caches,Logs,Application Support}`,
+/// with the products they may contain (`None` for every product).
+const IDE_VENDOR_DIRS: &[(&str, Option<&str>)] = &[
+    ("JetBrains", None),
+    // Android Studio is built on IntelliJ and uses the same layout under Google's folder.
+    ("Google", Some("AndroidStudio")),
+];
+
+/// Library folders in which the vendor folders are looked up.
+const IDE_LIBRARY_DIRS: &[&str] = &["Caches", "Logs", "Application Support"];
+
+/// A version of an IDE, e.g. `2024.1` or `2023.3.2`, compared component by component.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IdeVersion(pub Vec<u32>);
+
+impl std::fmt::Display for IdeVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(u32::to_string).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+/// Represents a cleaner for the caches, logs and settings of outdated IDE versions.
+///
+/// JetBrains IDEs (and Android Studio) keep a separate `<Product><Version>` folder per version,
+/// e.g. `~/Library/Caches/JetBrains/IntelliJIdea2023.2`, and never remove the old ones after an
+/// upgrade. For every product, folders of versions older than the newest *installed* version are
+/// removed, the installed versions being read from the IDE bundles in `/Applications` and `~/Applications`
+/// (see `installed_ide_version`). Products without an installed bundle are left alone, as are the
+/// folders of every installed version and of newer versions (EAP or trial builds).
+pub struct IdeCachesCleaner;
+
+impl IdeCachesCleaner {
+    pub fn new() -> Self {
+        IdeCachesCleaner // This simply returns an instance of the struct
+    }
+}
+
+impl Default for IdeCachesCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for IdeCachesCleaner {
+    fn name(&self) -> &str {
+        "IDE Caches"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let home = env::var("HOME").unwrap_or_default();
+        // Every versioned folder found, with its product and version.
+        let mut found: Vec<(String, IdeVersion, PathBuf)> = Vec::new();
+        for library_dir in IDE_LIBRARY_DIRS {
+            for (vendor, only_product) in IDE_VENDOR_DIRS {
+                let vendor_dir = PathBuf::from(format!("{}/Library/{}/{}", home, library_dir, vendor));
+                let Ok(entries) = fs::read_dir(&vendor_dir) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let Some((product, version)) = parse_ide_dir_name(&name) else {
+                        continue;
+                    };
+                    if only_product.is_none_or(|p| p == product) && entry.path().is_dir() {
+                        found.push((product, version, entry.path()));
+                    }
+                }
+            }
+        }
+
+        // The installed versions of each product, read from the IDE bundles.
+        let mut installed: HashMap<String, Vec<IdeVersion>> = HashMap::new();
+        for dir in [PathBuf::from("/Applications"), PathBuf::from(format!("{}/Applications", home))] {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for bundle in entries.flatten().map(|entry| entry.path()) {
+                if bundle.extension().is_some_and(|ext| ext == "app")
+                    && let Some((product, version)) = installed_ide_version(&bundle)
+                {
+                    installed.entry(product).or_default().push(version);
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        for (product, version, path) in &found {
+            let Some(versions) = installed.get(product) else {
+                log_debug!("{} is not installed, keeping its data: {}", product, path.display());
+                continue;
+            };
+            let Some(latest) = versions.iter().max() else {
+                continue;
+            };
+            if version < latest && !versions.contains(version) {
+                log_info!(
+                    "🧰 Outdated {} {} data (installed is {}): {}",
+                    product.bright_white(),
+                    version,
+                    latest.to_string().bright_green(),
+                    path.display().to_string().white().dimmed()
+                );
+                paths.push(path.clone());
+            }
+        }
+        paths
+    }
+
+    /// Each outdated IDE version folder gets its own row.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        path.to_path_buf()
+    }
+}
+
+/// Splits an IDE data folder name into its product and version.
+///
+/// The version starts at the first digit and must be made of at least two dot-separated
+/// numbers, so folders such as `Toolbox` or `consentOptions` are ignored.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::ide_caches::{IdeVersion, parse_ide_dir_name};
+///
+/// assert_eq!(
+///     parse_ide_dir_name("IntelliJIdea2023.2"),
+///     Some(("IntelliJIdea".to_string(), IdeVersion(vec![2023, 2])))
+/// );
+/// assert_eq!(
+///     parse_ide_dir_name("PyCharmCE2024.1.3"),
+///     Some(("PyCharmCE".to_string(), IdeVersion(vec![2024, 1, 3])))
+/// );
+/// assert_eq!(parse_ide_dir_name("Toolbox"), None);
+/// assert_eq!(parse_ide_dir_name("2024.1"), None);
+/// assert_eq!(parse_ide_dir_name("WebStorm2024"), None);
+/// assert_eq!(parse_ide_dir_name("Rider2024.1-backup"), None);
+///
+/// // Versions compare numerically, component by component.
+/// assert!(IdeVersion(vec![2023, 10]) > IdeVersion(vec![2023, 9]));
+/// assert!(IdeVersion(vec![2024, 1]) < IdeVersion(vec![2024, 1, 1]));
+/// ```
+pub fn parse_ide_dir_name(name: &str) -> Option<(String, IdeVersion)> {
+    let split = name.find(|c: char| c.is_ascii_digit())?;
+    let (product, version) = name.split_at(split);
+    if product.is_empty() {
+        return None;
+    }
+    let parts = version
+        .split('.')
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    if parts.len() < 2 {
+        return None;
+    }
+    Some((product.to_string(), IdeVersion(parts)))
+}
+
+/// Reads the product and version of the JetBrains IDE bundle at `bundle`, as used in its data folder
+/// names (e.g. `IntelliJIdea` and `2024.2`).
+///
+/// They come from the `idea.paths.selector` property in the bundle's `Info.plist` (`JVMOptions` >
+/// `Properties`), or from `dataDirectoryName` in `Contents/Resources/product-info.json` for recent
+/// versions, which no longer set it in `Info.plist`.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::ide_caches::{IdeVersion, installed_ide_version};
+/// use std::{env, fs};
+///
+/// let apps = env::temp_dir().join("osx-doctest-ide-bundles");
+/// let idea = apps.join("IntelliJ IDEA.app/Contents");
+/// fs::create_dir_all(&idea).unwrap();
+/// fs::write(
+///     idea.join("Info.plist"),
+///     r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <plist version="1.0"><dict>
+///     <key>CFBundleShortVersionString</key><string>2024.2.1</string>
+///     <key>JVMOptions</key><dict>
+///         <key>Properties</key><dict>
+///             <key>idea.paths.selector</key><string>IntelliJIdea2024.2</string>
+///         </dict>
+///     </dict>
+/// </dict></plist>"#,
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     installed_ide_version(&apps.join("IntelliJ IDEA.app")),
+///     Some(("IntelliJIdea".to_string(), IdeVersion(vec![2024, 2])))
+/// );
+///
+/// let pycharm = apps.join("PyCharm.app/Contents");
+/// fs::create_dir_all(pycharm.join("Resources")).unwrap();
+/// fs::write(pycharm.join("Info.plist"), r#"<plist version="1.0"><dict></dict></plist>"#).unwrap();
+/// fs::write(
+///     pycharm.join("Resources/product-info.json"),
+///     r#"{"name": "PyCharm", "version": "2024.3", "dataDirectoryName": "PyCharm2024.3"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     installed_ide_version(&apps.join("PyCharm.app")),
+///     Some(("PyCharm".to_string(), IdeVersion(vec![2024, 3])))
+/// );
+///
+/// fs::create_dir_all(apps.join("Slack.app/Contents")).unwrap();
+/// assert_eq!(installed_ide_version(&apps.join("Slack.app")), None);
+/// ```
+pub fn installed_ide_version(bundle: &Path) -> Option<(String, IdeVersion)> {
+    let contents = bundle.join("Contents");
+    let from_plist = Value::from_file(contents.join("Info.plist")).ok().and_then(|info| {
+        info.as_dictionary()?
+            .get("JVMOptions")?
+            .as_dictionary()?
+            .get("Properties")?
+            .as_dictionary()?
+            .get("idea.paths.selector")?
+            .as_string()
+            .map(str::to_string)
+    });
+    let selector = from_plist.or_else(|| {
+        let product_info = fs::read_to_string(contents.join("Resources/product-info.json")).ok()?;
+        let product_info: serde_json::Value = serde_json::from_str(&product_info).ok()?;
+        product_info.get("dataDirectoryName")?.as_str().map(str::to_string)
+    })?;
+    parse_ide_dir_name(&selector)
+}
@@ -0,0 +1,183 @@
+use crate::core::cleaners::Cleaner;
+use crate::utils::config::{env_list, env_u64};
+use crate::{log_debug, log_info, log_warn};
+use colored::Colorize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// File extensions of disk images, installer packages and archives that are usually
+/// only needed once, to install an application.
+const INSTALLER_EXTENSIONS: &[&str] = &["dmg", "pkg", "mpkg", "zip", "xip"];
+
+/// Environment variable holding extra directories to scan for installers, separated by commas.
+pub const INSTALLER_DIRS_ENV: &str = "OSX_INSTALLER_DIRS";
+/// Environment variable holding the minimum age, in days, of installers to report.
+pub const INSTALLER_MAX_AGE_DAYS_ENV: &str = "OSX_INSTALLER_MAX_AGE_DAYS";
+/// Default minimum age, in days, of installers to report.
+const DEFAULT_MAX_AGE_DAYS: u64 = 30;
+
+/// Represents a cleaner for installer leftovers (`.dmg`, `.pkg`, `.zip`, `.xip`) in `~/Downloads`.
+///
+/// Only installers older than `OSX_INSTALLER_MAX_AGE_DAYS` (30 days by default) are targeted.
+/// Disk images whose application is already installed in `/Applications` are flagged in the log.
+///
+/// This cleaner is report-only: archives in `~/Downloads` may be kept on purpose, so they are listed, never removed.
+pub struct InstallerLeftoversCleaner {
+    dirs: Vec<PathBuf>,
+    max_age: Duration,
+}
+
+impl InstallerLeftoversCleaner {
+    pub fn new() -> Self {
+        let home = env::var("HOME").unwrap_or_default();
+        let mut dirs = vec![PathBuf::from(&home).join("Downloads")];
+        dirs.extend(env_list(INSTALLER_DIRS_ENV).into_iter().map(PathBuf::from));
+
+        let max_age_days = env_u64(INSTALLER_MAX_AGE_DAYS_ENV, DEFAULT_MAX_AGE_DAYS);
+        InstallerLeftoversCleaner {
+            dirs,
+            max_age: Duration::from_secs(max_age_days.saturating_mul(24 * 60 * 60)),
+        }
+    }
+}
+
+impl Default for InstallerLeftoversCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for InstallerLeftoversCleaner {
+    fn name(&self) -> &str {
+        "Installer Leftovers"
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let mut installers = Vec::new();
+        let installed_apps = installed_app_names();
+        let now = SystemTime::now();
+
+        for dir in &self.dirs {
+            let entries = match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(_) => {
+                    log_debug!("Could not read installer directory: {}", dir.display());
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !is_installer(&path) {
+                    continue;
+                }
+
+                // Skip installers that were downloaded recently; they may not have been used yet.
+                let age = fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok());
+                match age {
+                    Some(age) if age >= self.max_age => {}
+                    Some(_) => continue,
+                    None => {
+                        log_warn!("Could not determine age of installer: {}", path.display());
+                        continue;
+                    }
+                }
+
+                if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("dmg")) {
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                    if let Some(app) = installed_app_for_installer(&file_name, &installed_apps) {
+                        log_info!(
+                            "📀 Installer for already installed app '{}': {}",
+                            app.bright_white(),
+                            path.display().to_string().white().dimmed()
+                        );
+                    }
+                }
+
+                installers.push(path);
+            }
+        }
+        installers
+    }
+}
+
+/// Returns `true` if `path` has an installer extension.
+/// `.pkg`/`.mpkg` installers can also be bundle directories, so the file type is not checked.
+fn is_installer(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| INSTALLER_EXTENSIONS.iter().any(|i| ext.eq_ignore_ascii_case(i)))
+}
+
+/// Collects the names (without `.app`) of applications installed in `/Applications` and `~/Applications`.
+fn installed_app_names() -> Vec<String> {
+    let home = env::var("HOME").unwrap_or_default();
+    let app_dirs = vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from(format!("{}/Applications", home)),
+    ];
+
+    let mut apps = Vec::new();
+    for dir in app_dirs {
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "app")
+                    && let Some(stem) = path.file_stem()
+                {
+                    apps.push(stem.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    apps
+}
+
+/// Lowercases `name` and keeps only its ASCII alphanumeric characters, so that
+/// `"Google Chrome"` and `"googlechrome.dmg"` can be compared.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Matches an installer file name against the names of installed application bundles.
+///
+/// Installers are usually named after the app, followed by a version or platform suffix
+/// (e.g. `Slack-4.41.105-macOS.dmg`), so the normalized file name must start with the
+/// normalized bundle name. The longest matching bundle name wins.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::installer_leftovers::installed_app_for_installer;
+/// let apps = vec!["Slack".to_string(), "Google Chrome".to_string()];
+/// assert_eq!(installed_app_for_installer("Slack-4.41.105-macOS.dmg", &apps), Some("Slack"));
+/// assert_eq!(installed_app_for_installer("googlechrome.dmg", &apps), Some("Google Chrome"));
+/// assert_eq!(installed_app_for_installer("Firefox 131.0.dmg", &apps), None);
+/// ```
+pub fn installed_app_for_installer<'a>(file_name: &str, installed_apps: &'a [String]) -> Option<&'a str> {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|s| normalize_name(&s.to_string_lossy()))
+        .unwrap_or_default();
+
+    installed_apps
+        .iter()
+        .map(|app| (app, normalize_name(app)))
+        // Very short names ("Go", "R") would match almost any installer.
+        .filter(|(_, normalized)| normalized.len() >= 3 && stem.starts_with(normalized.as_str()))
+        .max_by_key(|(_, normalized)| normalized.len())
+        .map(|(app, _)| app.as_str())
+}
@@ -0,0 +1,53 @@
+use crate::core::cleaners::Cleaner;
+use crate::core::ios_backups::{backups_dir, list_backups};
+use crate::log_info;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// Represents a report-only analyzer for local iOS/iPadOS device backups.
+///
+/// Each backup folder is reported on its own, and its device name and backup date are logged.
+/// Backups are removed one at a time with `osx ios-backups --delete <id>`.
+pub struct IosBackupsCleaner;
+
+impl IosBackupsCleaner {
+    pub fn new() -> Self {
+        IosBackupsCleaner // This simply returns an instance of the struct
+    }
+}
+
+impl Default for IosBackupsCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for IosBackupsCleaner {
+    fn name(&self) -> &str {
+        "iOS Backups"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        list_backups(&backups_dir())
+            .into_iter()
+            .map(|backup| {
+                log_info!(
+                    "📱 iOS backup of '{}' from {}: {}",
+                    backup.device_name.as_deref().unwrap_or("Unknown device").bright_white(),
+                    backup.backup_date.as_deref().unwrap_or("an unknown date").bright_white(),
+                    backup.id.white().dimmed()
+                );
+                backup.path
+            })
+            .collect()
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+
+    /// Every backup belongs to a different device (or point in time), so each one gets its own row.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        path.to_path_buf()
+    }
+}
@@ -0,0 +1,235 @@
+use crate::core::cleaners::Cleaner;
+use crate::log_debug;
+use crate::utils::config::env_list;
+use colored::Colorize;
+use glob::glob;
+use plist::Value;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable holding the languages to keep, separated by commas (e.g. `en,fr,pt-BR`).
+/// When it is not set, the user's preferred languages from the system settings are kept.
+pub const KEEP_LANGUAGES_ENV: &str = "OSX_KEEP_LANGUAGES";
+
+/// Localizations that are always kept. `Base` holds the interface files every language builds on.
+const ALWAYS_KEPT_LANGUAGES: &[&str] = &["Base", "en"];
+
+/// Old-style localization folder names still shipped by some apps, with their language code.
+const LEGACY_LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("italian", "it"),
+    ("japanese", "ja"),
+    ("spanish", "es"),
+    ("dutch", "nl"),
+];
+
+/// Represents a cleaner for unused localizations (`.lproj` folders) of application bundles.
+///
+/// Localizations are removed from `/Applications/*.app/Contents/Resources` unless their language
+/// is in the keep-list: the user's preferred languages (or `OSX_KEEP_LANGUAGES`) plus `Base` and `en`.
+/// Removing sealed resources invalidates an app's code signature, so the cleaner comes in two flavors:
+/// one for apps that can safely lose their localizations, and a report-only one for the others.
+pub struct LanguageFilesCleaner {
+    keep: Vec<String>,
+    signed_apps: bool,
+}
+
+impl LanguageFilesCleaner {
+    /// Creates the cleaner for apps whose signature does not seal the unused localizations.
+    pub fn new() -> Self {
+        LanguageFilesCleaner {
+            keep: kept_languages(),
+            signed_apps: false,
+        }
+    }
+
+    /// Creates the report-only cleaner for apps whose signature would break if the unused
+    /// localizations were removed.
+    pub fn for_signed_apps() -> Self {
+        LanguageFilesCleaner {
+            keep: kept_languages(),
+            signed_apps: true,
+        }
+    }
+}
+
+impl Default for LanguageFilesCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for LanguageFilesCleaner {
+    fn name(&self) -> &str {
+        if self.signed_apps {
+            "Language Files (Signed Apps)"
+        } else {
+            "Language Files"
+        }
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let Ok(apps) = glob("/Applications/*.app") else {
+            return paths;
+        };
+        for app in apps.flatten() {
+            let Ok(localizations) = glob(&format!(
+                "{}/Contents/Resources/*.lproj",
+                glob::Pattern::escape(&app.to_string_lossy())
+            )) else {
+                continue;
+            };
+            let unused: Vec<PathBuf> = localizations
+                .flatten()
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| !is_kept_localization(n, &self.keep))
+                })
+                .collect();
+            if unused.is_empty() {
+                continue;
+            }
+
+            if removal_breaks_signature(&app, &unused) == self.signed_apps {
+                paths.extend(unused);
+            } else {
+                log_debug!("Skipping {} for {}", app.display(), self.name());
+            }
+        }
+        paths
+    }
+
+    fn is_report_only(&self) -> bool {
+        self.signed_apps
+    }
+
+    /// Localizations are summarized per app bundle, so the tables show the savings of each app.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        // `<App>.app/Contents/Resources/<lang>.lproj`
+        path.ancestors().nth(3).unwrap_or(path).to_path_buf()
+    }
+}
+
+/// Returns the languages whose localizations are kept: `OSX_KEEP_LANGUAGES` if set,
+/// otherwise the user's preferred languages, always followed by `Base` and `en`.
+fn kept_languages() -> Vec<String> {
+    let mut keep = env_list(KEEP_LANGUAGES_ENV);
+    if keep.is_empty() {
+        keep = preferred_languages();
+    }
+    keep.extend(ALWAYS_KEPT_LANGUAGES.iter().map(|l| l.to_string()));
+    log_debug!("Keeping localizations for: {}", keep.join(", "));
+    keep
+}
+
+/// Reads the user's preferred languages (`AppleLanguages`) from the global preferences.
+fn preferred_languages() -> Vec<String> {
+    let home = env::var("HOME").unwrap_or_default();
+    let preferences = PathBuf::from(format!("{}/Library/Preferences/.GlobalPreferences.plist", home));
+    match Value::from_file(&preferences) {
+        Ok(value) => value
+            .as_dictionary()
+            .and_then(|d| d.get("AppleLanguages"))
+            .and_then(Value::as_array)
+            .map(|languages| {
+                languages
+                    .iter()
+                    .filter_map(Value::as_string)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Err(e) => {
+            log_debug!("Could not read preferred languages from {}: {}", preferences.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Returns the primary language subtag of a language or localization name, lowercased:
+/// `pt-BR`, `pt_PT` and `pt` all yield `pt`, and legacy names such as `French` yield `fr`.
+fn primary_language(name: &str) -> String {
+    let name = name.trim_end_matches(".lproj").to_lowercase();
+    let primary = name.split(['-', '_']).next().unwrap_or_default();
+    LEGACY_LANGUAGE_NAMES
+        .iter()
+        .find(|(legacy, _)| *legacy == primary)
+        .map_or(primary, |(_, code)| code)
+        .to_string()
+}
+
+/// Returns `true` if the localization folder `lproj_name` belongs to a language in `keep`.
+///
+/// Languages are compared by their primary subtag, so keeping `pt-BR` also keeps `pt_PT.lproj`:
+/// regional variants often fall back on each other, and they are small compared to other languages.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::language_files::is_kept_localization;
+///
+/// let keep = vec!["pt-BR".to_string(), "Base".to_string(), "en".to_string()];
+/// assert!(is_kept_localization("Base.lproj", &keep));
+/// assert!(is_kept_localization("en_GB.lproj", &keep));
+/// assert!(is_kept_localization("English.lproj", &keep));
+/// assert!(is_kept_localization("pt_PT.lproj", &keep));
+/// assert!(!is_kept_localization("fr.lproj", &keep));
+/// assert!(!is_kept_localization("zh-Hans.lproj", &keep));
+/// ```
+pub fn is_kept_localization(lproj_name: &str, keep: &[String]) -> bool {
+    let language = primary_language(lproj_name);
+    keep.iter().any(|k| primary_language(k) == language)
+}
+
+/// Returns `true` if removing the `localizations` of `app` would invalidate its code signature.
+///
+/// Unsigned apps never break. For signed apps, the sealed resources listed in
+/// `Contents/_CodeSignature/CodeResources` are checked: files under the localizations that are
+/// not marked `optional` are verified by the signature. An unreadable seal is treated as breaking.
+fn removal_breaks_signature(app: &Path, localizations: &[PathBuf]) -> bool {
+    let signature_dir = app.join("Contents/_CodeSignature");
+    if !signature_dir.exists() {
+        return false;
+    }
+
+    let code_resources = signature_dir.join("CodeResources");
+    let sealed_files = match Value::from_file(&code_resources) {
+        Ok(value) => value.into_dictionary().and_then(|mut d| d.remove("files2")),
+        Err(e) => {
+            log_debug!("Could not read code signature seal {}: {}", code_resources.display(), e);
+            return true;
+        }
+    };
+    let Some(sealed_files) = sealed_files.and_then(Value::into_dictionary) else {
+        return true;
+    };
+
+    // Sealed paths are relative to `Contents`, e.g. `Resources/fr.lproj/Localizable.strings`.
+    let prefixes: Vec<String> = localizations
+        .iter()
+        .filter_map(|l| l.file_name())
+        .map(|name| format!("Resources/{}/", name.to_string_lossy()))
+        .collect();
+    let breaking = sealed_files.iter().find(|(file, seal)| {
+        let optional = seal
+            .as_dictionary()
+            .and_then(|s| s.get("optional"))
+            .and_then(Value::as_boolean)
+            .unwrap_or(false);
+        !optional && prefixes.iter().any(|prefix| file.starts_with(prefix.as_str()))
+    });
+    if let Some((file, _)) = breaking {
+        log_debug!(
+            "Localizations of {} are sealed by its signature ({})",
+            app.display().to_string().white().dimmed(),
+            file
+        );
+    }
+    breaking.is_some()
+}
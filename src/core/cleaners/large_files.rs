@@ -3,6 +3,20 @@ use std::{env, fs, path::PathBuf};
 use walkdir::WalkDir;
 
 
+/// Common user directories scanned for large (and duplicate) files, relative to the home directory.
+pub const COMMON_USER_DIRS: &[&str] = &[
+    "Downloads", "Desktop", "Documents", "Movies", "Music", "Pictures",
+];
+
+/// Returns the absolute paths of the `COMMON_USER_DIRS` for the current user.
+pub fn common_user_dirs() -> Vec<PathBuf> {
+    let home = env::var("HOME").unwrap_or_default();
+    COMMON_USER_DIRS
+        .iter()
+        .map(|dir_name| PathBuf::from(&home).join(dir_name))
+        .collect()
+}
+
 /// Represents a cleaner for large files in common user directories.
 pub struct LargeFilesCleaner;
 
@@ -19,15 +33,10 @@ impl Cleaner for LargeFilesCleaner {
 
     fn find_paths(&self) -> Vec<PathBuf> {
         let mut large_files = Vec::new();
-        let home = env::var("HOME").unwrap_or_default();
-        let common_user_dirs = vec![
-            "Downloads", "Desktop", "Documents", "Movies", "Music", "Pictures",
-        ];
         // Define what constitutes a "large file" (e.g., 100 MB)
         const LARGE_FILE_THRESHOLD_BYTES: u64 = 100 * 1024 * 1024; // 100 MB
 
-        for dir_name in common_user_dirs {
-            let current_dir = PathBuf::from(&home).join(dir_name);
+        for current_dir in common_user_dirs() {
             if !current_dir.exists() {
                 continue;
             }
@@ -0,0 +1,404 @@
+use crate::core::cleaners::{Cleaner, format_bytes};
+use crate::utils::config::env_u64;
+use crate::utils::filesystem::{is_writable, remove_path};
+use crate::{log_debug, log_info, log_warn};
+use colored::Colorize;
+use flate2::{Compression, write::GzEncoder};
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+use walkdir::WalkDir;
+
+/// Environment variable selecting how logs are cleaned: `rotate` (the default) or `delete`,
+/// which removes the whole log folders like `UserLogsCleaner` and `CrashReporterLogsCleaner` do.
+pub const LOG_MODE_ENV: &str = "OSX_LOG_MODE";
+/// Environment variable holding the number of files kept per log family, the active log included.
+pub const LOG_KEEP_FILES_ENV: &str = "OSX_LOG_KEEP_FILES";
+/// Environment variable holding the size, in MB, above which an active log is truncated.
+pub const LOG_MAX_SIZE_MB_ENV: &str = "OSX_LOG_MAX_SIZE_MB";
+/// Environment variable holding the age, in days, after which diagnostic reports are deleted.
+pub const LOG_REPORT_MAX_AGE_DAYS_ENV: &str = "OSX_LOG_REPORT_MAX_AGE_DAYS";
+
+const DEFAULT_KEEP_FILES: u64 = 5;
+const DEFAULT_MAX_SIZE_MB: u64 = 50;
+const DEFAULT_REPORT_MAX_AGE_DAYS: u64 = 30;
+
+/// Extensions of already compressed (rotated) log files.
+const COMPRESSED_EXTENSIONS: &[&str] = &["gz", "bz2", "xz", "zip"];
+/// Extensions of crash and diagnostic reports, which are cleaned by age instead of being rotated.
+const REPORT_EXTENSIONS: &[&str] = &["ips", "crash", "diag"];
+
+/// Returns `true` unless `OSX_LOG_MODE=delete` asks for the old behavior of deleting log folders.
+pub fn log_rotation_enabled() -> bool {
+    !env::var(LOG_MODE_ENV).is_ok_and(|mode| mode.trim().eq_ignore_ascii_case("delete"))
+}
+
+/// What the log rotation does with a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogAction {
+    /// Gzip a rotated log next to the original, then remove the original.
+    Compress,
+    /// Empty an oversized active log, keeping the file for the process writing to it.
+    Truncate,
+    /// Delete an old rotated log or an old diagnostic report.
+    Delete,
+}
+
+/// A log file considered by the rotation.
+#[derive(Clone, Debug)]
+pub struct LogFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+/// Limits applied by the log rotation.
+#[derive(Clone, Debug)]
+pub struct LogRotationPolicy {
+    /// Number of files kept per log family, the active log included.
+    pub keep_files: usize,
+    /// Size in bytes above which the active log of a family is truncated.
+    pub max_active_size: u64,
+    /// Age after which diagnostic reports are deleted.
+    pub report_max_age: Duration,
+}
+
+impl LogRotationPolicy {
+    /// Reads the policy from the `OSX_LOG_*` environment variables.
+    pub fn from_env() -> Self {
+        LogRotationPolicy {
+            keep_files: env_u64(LOG_KEEP_FILES_ENV, DEFAULT_KEEP_FILES).max(1) as usize,
+            max_active_size: env_u64(LOG_MAX_SIZE_MB_ENV, DEFAULT_MAX_SIZE_MB).saturating_mul(1024 * 1024),
+            report_max_age: Duration::from_secs(
+                env_u64(LOG_REPORT_MAX_AGE_DAYS_ENV, DEFAULT_REPORT_MAX_AGE_DAYS).saturating_mul(24 * 60 * 60),
+            ),
+        }
+    }
+}
+
+/// Represents a log-aware cleaner that rotates logs instead of deleting whole log folders.
+///
+/// For every log family (e.g. `system.log`, `system.log.0.gz`, `system.log.1.gz`), the newest
+/// `OSX_LOG_KEEP_FILES` files are kept: rotated `.log` files among them are gzip-compressed, and the
+/// active log is truncated when it grows over `OSX_LOG_MAX_SIZE_MB`. Older files are deleted, as are
+/// `.ips`/`.crash`/`.diag` reports (and CrashReporter plists) older than `OSX_LOG_REPORT_MAX_AGE_DAYS`.
+/// Files the current user cannot modify are left alone.
+pub struct LogRotationCleaner {
+    roots: Vec<PathBuf>,
+    /// Folders whose files are all diagnostic reports, whatever their extension.
+    report_dirs: Vec<PathBuf>,
+    policy: LogRotationPolicy,
+    /// The action planned for every path returned by `find_paths`, used by `clean_path`.
+    actions: Mutex<HashMap<PathBuf, LogAction>>,
+}
+
+impl LogRotationCleaner {
+    pub fn new() -> Self {
+        let home = env::var("HOME").unwrap_or_default();
+        LogRotationCleaner {
+            roots: vec![
+                PathBuf::from(format!("{}/Library/Logs", home)),
+                PathBuf::from("/Library/Logs"),
+                PathBuf::from("/var/log"),
+            ],
+            // The folder `CrashReporterLogsCleaner` deletes as a whole.
+            report_dirs: vec![PathBuf::from(format!(
+                "{}/Library/Application Support/CrashReporter",
+                home
+            ))],
+            policy: LogRotationPolicy::from_env(),
+            actions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Collects the regular files below `root` that the current user may modify.
+    fn collect_files(root: &Path) -> Vec<LogFile> {
+        WalkDir::new(root)
+            .same_file_system(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| {
+                // Deleting or compressing needs write access to the folder, truncating to the file.
+                let writable = is_writable(e.path()) && e.path().parent().is_some_and(is_writable);
+                if !writable {
+                    log_debug!("Skipping log without write access: {}", e.path().display());
+                }
+                writable
+            })
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                Some(LogFile {
+                    path: e.into_path(),
+                    size: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for LogRotationCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for LogRotationCleaner {
+    fn name(&self) -> &str {
+        "Log Rotation"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let now = SystemTime::now();
+        let is_old = |file: &LogFile| {
+            now.duration_since(file.modified)
+                .is_ok_and(|age| age > self.policy.report_max_age)
+        };
+
+        let mut planned = Vec::new();
+        for dir in &self.report_dirs {
+            for file in Self::collect_files(dir).into_iter().filter(|f| is_old(f)) {
+                planned.push((file.path, LogAction::Delete));
+            }
+        }
+        for root in &self.roots {
+            let (reports, logs): (Vec<LogFile>, Vec<LogFile>) = Self::collect_files(root)
+                .into_iter()
+                .partition(|f| has_extension(&f.path, REPORT_EXTENSIONS));
+            for report in reports.into_iter().filter(|f| is_old(f)) {
+                planned.push((report.path, LogAction::Delete));
+            }
+            planned.extend(plan_rotation(logs, &self.policy));
+        }
+
+        // Compressed logs are counted at their full size: what compressing frees is only known afterwards.
+        let compressed: Vec<u64> = planned
+            .iter()
+            .filter(|(_, action)| *action == LogAction::Compress)
+            .filter_map(|(path, _)| fs::metadata(path).ok().map(|m| m.len()))
+            .collect();
+        if !compressed.is_empty() {
+            log_info!(
+                "📦 {} rotated log(s) are compressed: their full size ({}) is an estimate, compressing frees less",
+                compressed.len(),
+                format_bytes(compressed.iter().sum())
+            );
+        }
+
+        let mut actions = self.actions.lock().unwrap();
+        planned
+            .into_iter()
+            .map(|(path, action)| {
+                log_debug!("Planned log rotation: {:?} {}", action, path.display());
+                actions.insert(path.clone(), action);
+                path
+            })
+            .collect()
+    }
+
+    fn clean_path(&self, path: &Path, size: u64, dry_run: bool) -> io::Result<u64> {
+        let action = self.actions.lock().unwrap().get(path).copied();
+        if dry_run {
+            return Ok(size);
+        }
+        match action {
+            // The bytes actually freed, not the full size reported for the file.
+            Some(LogAction::Compress) => match compress_log(path)? {
+                Some(freed) => {
+                    log_debug!("Compressed log {} ({} bytes freed)", path.display(), freed);
+                    Ok(freed)
+                }
+                None => {
+                    log_warn!("Not compressing {}: an archive with its name already exists", path.display());
+                    Ok(0)
+                }
+            },
+            Some(LogAction::Truncate) => {
+                OpenOptions::new().write(true).open(path)?.set_len(0)?;
+                log_info!("✂️  Truncated oversized log: {}", path.display().to_string().white().dimmed());
+                Ok(size)
+            }
+            Some(LogAction::Delete) | None => remove_path(path, false).map(|_| size),
+        }
+    }
+}
+
+/// Returns the family of a log file name: the name without its compression extension and rotation
+/// index, with every run of digits replaced by `#`, so dated and numbered logs fall in the same family.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::log_rotation::log_family;
+///
+/// assert_eq!(log_family("system.log"), "system.log");
+/// assert_eq!(log_family("system.log.0.gz"), "system.log");
+/// assert_eq!(log_family("install.log.12"), "install.log");
+/// assert_eq!(log_family("app-2024-05-01.log"), "app-#-#-#.log");
+/// assert_eq!(log_family("app-2024-05-02.log.gz"), "app-#-#-#.log");
+/// ```
+pub fn log_family(file_name: &str) -> String {
+    let mut name = file_name;
+    if let Some((stem, extension)) = name.rsplit_once('.')
+        && COMPRESSED_EXTENSIONS.contains(&extension)
+    {
+        name = stem;
+    }
+    if let Some((stem, index)) = name.rsplit_once('.')
+        && !index.is_empty()
+        && index.chars().all(|c| c.is_ascii_digit())
+    {
+        name = stem;
+    }
+
+    let mut family = String::with_capacity(name.len());
+    for c in name.chars() {
+        if !c.is_ascii_digit() {
+            family.push(c);
+        } else if !family.ends_with('#') {
+            family.push('#');
+        }
+    }
+    family
+}
+
+/// Plans the rotation of `files`: files are grouped into families per folder, and within each family,
+/// from newest to oldest, the active log is truncated if oversized, the next kept files are compressed
+/// if they are uncompressed `.log` files, and the remaining ones are deleted.
+///
+/// Only logs are rotated: files whose name contains `.log` (`wifi.log`, `install.log.3`), or compressed
+/// rotations of them (`wifi.log.1.gz`). Anything else (`.json`, `.db`, `.plist`, state files) is left alone.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::log_rotation::{LogAction, LogFile, LogRotationPolicy, plan_rotation};
+/// use std::time::{Duration, SystemTime};
+///
+/// let day = Duration::from_secs(24 * 60 * 60);
+/// let file = |name: &str, size: u64, age_days: u32| LogFile {
+///     path: format!("/var/log/{}", name).into(),
+///     size,
+///     modified: SystemTime::now() - day * age_days,
+/// };
+/// let policy = LogRotationPolicy { keep_files: 3, max_active_size: 100, report_max_age: day * 30 };
+///
+/// let mut plan = plan_rotation(
+///     vec![
+///         file("wifi.log", 500, 0),
+///         file("wifi.log.0", 80, 1),
+///         file("wifi.log.1.gz", 20, 2),
+///         file("wifi.log.2.gz", 20, 3),
+///         file("small.log", 10, 0),
+///         // Not logs: never rotated, however many there are.
+///         file("state-1.json", 10, 1),
+///         file("state-2.json", 10, 2),
+///         file("state-3.json", 10, 3),
+///         file("state-4.json", 10, 4),
+///         file("cache.db.gz", 10, 5),
+///     ],
+///     &policy,
+/// );
+/// plan.sort();
+/// assert_eq!(
+///     plan,
+///     vec![
+///         ("/var/log/wifi.log".into(), LogAction::Truncate),
+///         ("/var/log/wifi.log.0".into(), LogAction::Compress),
+///         ("/var/log/wifi.log.2.gz".into(), LogAction::Delete),
+///     ]
+/// );
+/// ```
+pub fn plan_rotation(files: Vec<LogFile>, policy: &LogRotationPolicy) -> Vec<(PathBuf, LogAction)> {
+    let mut families: HashMap<(PathBuf, String), Vec<LogFile>> = HashMap::new();
+    for file in files {
+        let Some(name) = file.path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if !is_log_name(&name) {
+            continue;
+        }
+        let folder = file.path.parent().unwrap_or(Path::new("")).to_path_buf();
+        families.entry((folder, log_family(&name))).or_default().push(file);
+    }
+
+    let mut plan = Vec::new();
+    for mut family in families.into_values() {
+        family.sort_by_key(|file| std::cmp::Reverse(file.modified));
+        for (index, file) in family.into_iter().enumerate() {
+            let is_plain_log = !has_extension(&file.path, COMPRESSED_EXTENSIONS)
+                && file.path.file_name().is_some_and(|n| n.to_string_lossy().contains(".log"));
+            let action = if index == 0 {
+                (is_plain_log && file.size > policy.max_active_size).then_some(LogAction::Truncate)
+            } else if index < policy.keep_files {
+                is_plain_log.then_some(LogAction::Compress)
+            } else {
+                Some(LogAction::Delete)
+            };
+            if let Some(action) = action {
+                plan.push((file.path, action));
+            }
+        }
+    }
+    plan
+}
+
+/// Returns `true` if `file_name` is a log, or a compressed rotation of one: its name, without a
+/// compression extension, contains `.log`.
+fn is_log_name(file_name: &str) -> bool {
+    let name = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if COMPRESSED_EXTENSIONS.contains(&extension) => stem,
+        _ => file_name,
+    };
+    name.contains(".log")
+}
+
+/// Returns `true` if the extension of `path` is one of `extensions`.
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.contains(&e))
+}
+
+/// Gzips `path` into `<path>.gz`, keeping its modification time so the rotation order is preserved,
+/// then removes the original. Returns the number of bytes freed, or `None` when `<path>.gz` already
+/// exists: the existing archive is never overwritten, and the log is left as it is.
+fn compress_log(path: &Path) -> io::Result<Option<u64>> {
+    let mut original = File::open(path)?;
+    let metadata = original.metadata()?;
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+
+    let archive = match OpenOptions::new().write(true).create_new(true).open(&gz_path) {
+        Ok(archive) => archive,
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let result = (|| {
+        let mut encoder = GzEncoder::new(archive, Compression::default());
+        io::copy(&mut original, &mut encoder)?;
+        let compressed = encoder.finish()?;
+        compressed.set_modified(metadata.modified()?)?;
+        compressed.set_permissions(metadata.permissions())?;
+        compressed.metadata()
+    })();
+    match result {
+        Ok(compressed) => {
+            fs::remove_file(path)?;
+            Ok(Some(metadata.len().saturating_sub(compressed.len())))
+        }
+        Err(e) => {
+            // Never leave a partial archive next to the original (it was created above, not overwritten).
+            let _ = fs::remove_file(&gz_path);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::core::cleaners::Cleaner;
+use std::env;
+use std::path::PathBuf;
+
+/// Represents a report-only analyzer for attachments opened from Apple Mail.
+///
+/// Mail copies every attachment the user opens into its "Mail Downloads" folder and rarely
+/// cleans it up. The folder is personal data, so it is only reported, never cleaned.
+pub struct MailDownloadsCleaner;
+
+impl MailDownloadsCleaner {
+    pub fn new() -> Self {
+        MailDownloadsCleaner // This simply returns an instance of the struct
+    }
+}
+
+impl Default for MailDownloadsCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for MailDownloadsCleaner {
+    fn name(&self) -> &str {
+        "Mail Downloads"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let home = env::var("HOME").unwrap_or_default();
+        vec![
+            // Location used by older, non-sandboxed versions of Mail.
+            PathBuf::from(format!("{}/Library/Mail Downloads", home)),
+            // Location used by the sandboxed Mail app.
+            PathBuf::from(format!(
+                "{}/Library/Containers/com.apple.mail/Data/Library/Mail Downloads",
+                home
+            )),
+        ]
+        .into_iter()
+        .filter(|p| p.exists())
+        .collect()
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,38 @@
+use crate::core::cleaners::Cleaner;
+use std::env;
+use std::path::PathBuf;
+
+/// Represents a report-only analyzer for the attachments of the Messages app.
+///
+/// Every photo, video and file ever sent or received in Messages is kept in
+/// `~/Library/Messages/Attachments`. This is personal data, so it is only reported;
+/// attachments should be removed from within Messages so conversations stay consistent.
+pub struct MessagesAttachmentsCleaner;
+
+impl MessagesAttachmentsCleaner {
+    pub fn new() -> Self {
+        MessagesAttachmentsCleaner // This simply returns an instance of the struct
+    }
+}
+
+impl Default for MessagesAttachmentsCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for MessagesAttachmentsCleaner {
+    fn name(&self) -> &str {
+        "Messages Attachments"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let home = env::var("HOME").unwrap_or_default();
+        let attachments = PathBuf::from(format!("{}/Library/Messages/Attachments", home));
+        if attachments.exists() { vec![attachments] } else { Vec::new() }
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+}
@@ -1,11 +1,14 @@
+use crate::utils::filesystem::{calculate_dir_size, remove_path};
 use crate::{log_debug, log_warn};
 use colored::Colorize;
 use rayon::prelude::*; // Used for parallel iteration over collections.
 use std::{
     fs, // File system operations (e.g., metadata, read_dir).
-    path::PathBuf, // Represents file system paths.
+    io, // I/O results returned by size calculations.
+    path::{Path, PathBuf}, // Represents file system paths.
     process::Command, // For executing external commands (e.g., csrutil).
     sync::{Arc, Mutex}, // For shared, thread-safe access to data.
+    time::SystemTime, // Modification and access times, e.g. when a toolchain or app was last used.
 };
 use tabled::Tabled; // Trait for generating formatted tables.
 
@@ -20,6 +23,9 @@ pub struct CleanupEntry {
     // The file system path that was cleaned.
     #[tabled(rename = "Path")]
     pub path: String,
+    // The number of paths aggregated into this entry (e.g., many small files under one folder).
+    #[tabled(rename = "Items")]
+    pub items: String,
     // The size of the cleaned path, formatted as a human-readable string (e.g., "1.2 GB").
     #[tabled(rename = "Size")]
     pub size: String,
@@ -56,6 +62,7 @@ pub struct PathToCheck {
     pub initial_size: u64, // The size of the path in bytes.
     pub formatted_size: String, // The human-readable formatted size.
     pub cleaner_name: String, // The name of the cleaner that identified this path.
+    pub summary_path: PathBuf, // The path this entry is grouped under in summaries (see `Cleaner::summary_path`).
 }
 
 /// Defines a common interface for any entity or component that can perform a specific cleaning task.
@@ -69,6 +76,46 @@ pub trait Cleaner: Send + Sync {
     /// Each concrete `Cleaner` implementation must provide its own logic for this method.
     fn find_paths(&self) -> Vec<PathBuf>;
 
+    /// Returns `true` if this cleaner only reports what it finds.
+    /// The orchestrator lists the paths of report-only cleaners in their own table
+    /// and never hands them to `remove_path`, even outside of a dry run.
+    fn is_report_only(&self) -> bool {
+        false
+    }
+
+    /// Returns the path under which `path` is grouped in the cleanup summaries.
+    /// By default, paths are grouped under their parent directory; cleaners that find many
+    /// small files in deep trees can override this to group them under a scan root instead.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        if path.components().count() > 1 && path.file_name().is_some() {
+            // For files within a directory, group under the parent directory
+            path.parent().unwrap_or(path).to_path_buf()
+        } else {
+            // For top-level paths, group under the path itself
+            path.to_path_buf()
+        }
+    }
+
+    /// Returns the number of bytes reported (and freed) for `path`.
+    /// By default this is the size of the path on disk; cleaners that only free part of a file
+    /// can override it.
+    fn path_size(&self, path: &Path) -> io::Result<u64> {
+        calculate_dir_size(path)
+    }
+
+    /// Returns the paths this cleaner deliberately left out during the last `find_paths`, with the reason
+    /// (e.g. a read-only volume). They are added to the skipped entries by `clean`.
+    fn skipped_paths(&self) -> Vec<SkippedEntry> {
+        Vec::new()
+    }
+
+    /// Cleans a single path found by this cleaner and returns the number of bytes freed.
+    /// `size` is the size reported for the path by `path_size`. By default the path is removed;
+    /// cleaners that rewrite files instead of deleting them (e.g. log rotation) can override it.
+    fn clean_path(&self, path: &Path, size: u64, dry_run: bool) -> io::Result<u64> {
+        remove_path(path, dry_run).map(|_| size)
+    }
+
     /// Executes the cleaning logic for this specific cleaner.
     /// This method now primarily focuses on identifying paths, calculating their sizes,
     /// applying ignore filters, and logging the "Checking" phase.
@@ -94,6 +141,7 @@ pub trait Cleaner: Send + Sync {
 
         // Call the cleaner-specific `find_paths` method to get initial candidates.
         let mut paths = self.find_paths();
+        skipped_entries.lock().unwrap().extend(self.skipped_paths());
 
         // Apply the ignore filter to the paths found by this cleaner.
         let initial_count = paths.len();
@@ -126,6 +174,7 @@ pub trait Cleaner: Send + Sync {
                         initial_size: size,
                         formatted_size,
                         cleaner_name: self.name().to_string(),
+                        summary_path: self.summary_path(path),
                     });
                 }
                 None => {
@@ -184,9 +233,12 @@ pub fn calculate_size(path: &PathBuf) -> Option<(u64, String)> {
                 }
             }
         }
+    } else if let Ok(metadata) = fs::symlink_metadata(path) {
+        // Path exists but is neither a file nor a directory (e.g., a broken symlink); count the entry itself.
+        size = metadata.len();
     } else {
-        // Path is neither a file nor a directory (e.g., a broken symlink).
-        log_warn!("⚠️ Path is neither file nor directory: {}", path.display());
+        // Path does not exist or cannot be accessed.
+        log_warn!("⚠️ Path does not exist or cannot be accessed: {}", path.display());
         return None;
     }
     // Return the total size and its human-readable formatted string.
@@ -207,6 +259,18 @@ pub fn format_bytes(bytes: u64) -> String {
     }
 }
 
+// Formats how long ago `time` was, in days (e.g. "today", "12 days ago").
+pub fn format_age(time: Option<SystemTime>) -> String {
+    match time.and_then(|t| SystemTime::now().duration_since(t).ok()) {
+        Some(age) => match age.as_secs() / (24 * 60 * 60) {
+            0 => "today".to_string(),
+            1 => "1 day ago".to_string(),
+            days => format!("{} days ago", days),
+        },
+        None => "unknown".to_string(),
+    }
+}
+
 /// Checks if System Integrity Protection (SIP) is enabled on macOS.
 /// This is done by executing the `csrutil status` command and checking its output.
 pub fn is_sip_enabled() -> bool {
@@ -237,5 +301,35 @@ pub mod trash;
 pub use self::trash::TrashCleaner;
 pub mod large_files;
 pub use self::large_files::LargeFilesCleaner;
+pub mod installer_leftovers;
+pub use self::installer_leftovers::InstallerLeftoversCleaner;
 mod browser_caches; // This module is declared but not `pub` re-exported directly.
-pub use self::browser_caches::BrowserCachesCleaner; // Only the struct is re-exported.
\ No newline at end of file
+pub use self::browser_caches::BrowserCachesCleaner; // Only the struct is re-exported.
+pub mod electron_app_caches;
+pub use self::electron_app_caches::ElectronAppCachesCleaner;
+pub mod duplicate_files;
+pub use self::duplicate_files::DuplicateFilesCleaner;
+pub mod finder_metadata;
+pub use self::finder_metadata::FinderMetadataCleaner;
+pub mod broken_symlinks;
+pub use self::broken_symlinks::BrokenSymlinksCleaner;
+pub mod mail_downloads;
+pub use self::mail_downloads::MailDownloadsCleaner;
+pub mod messages_attachments;
+pub use self::messages_attachments::MessagesAttachmentsCleaner;
+pub mod ios_backups;
+pub use self::ios_backups::IosBackupsCleaner;
+pub mod language_files;
+pub use self::language_files::LanguageFilesCleaner;
+pub mod universal_binaries;
+pub use self::universal_binaries::UniversalBinariesCleaner;
+pub mod orphaned_app_data;
+pub use self::orphaned_app_data::OrphanedAppDataCleaner;
+pub mod vm_disk_images;
+pub use self::vm_disk_images::VmDiskImagesCleaner;
+pub mod ide_caches;
+pub use self::ide_caches::IdeCachesCleaner;
+pub mod log_rotation;
+pub use self::log_rotation::LogRotationCleaner;
+pub mod toolchains;
+pub use self::toolchains::ToolchainsCleaner;
\ No newline at end of file
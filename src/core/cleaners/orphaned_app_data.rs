@@ -0,0 +1,230 @@
+use crate::core::cleaners::Cleaner;
+use crate::core::installed_apps::{InstalledApp, installed_apps};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Data folders created by macOS itself in `~/Library/Application Support` and `~/Library/Caches`,
+/// which are not named after any app bundle.
+const SYSTEM_DATA_NAMES: &[&str] = &[
+    "accountsd",
+    "AddressBook",
+    "Animoji",
+    "CallHistoryDB",
+    "CallHistoryTransactions",
+    "CloudDocs",
+    "CrashReporter",
+    "DiskImages",
+    "FileProvider",
+    "homed",
+    "icdd",
+    "iCloud",
+    "identityservicesd",
+    "Knowledge",
+    "MobileSync",
+    "networkserviceproxy",
+    "SyncServices",
+];
+
+/// Represents a report-only cleaner for application data whose app is no longer installed.
+///
+/// It is the inverse of `MacApp::find_related_paths`: instead of starting from an app and looking
+/// for its data, it starts from the data folders and looks for an app owning them. This catches the
+/// leftovers of apps removed by dragging them to the Trash. Ownership is a heuristic, so the entries
+/// are only reported; use `osx uninstall` or `--ignore` once an entry has been checked.
+pub struct OrphanedAppDataCleaner {
+    owners: AppOwners,
+}
+
+impl OrphanedAppDataCleaner {
+    pub fn new() -> Self {
+        OrphanedAppDataCleaner {
+            owners: AppOwners::new(&installed_apps()),
+        }
+    }
+}
+
+impl Default for OrphanedAppDataCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for OrphanedAppDataCleaner {
+    fn name(&self) -> &str {
+        "Orphaned App Data"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let home = env::var("HOME").unwrap_or_default();
+        let library = PathBuf::from(format!("{}/Library", home));
+        let mut paths = Vec::new();
+        for location in ["Application Support", "Containers", "Group Containers", "Preferences", "Caches"] {
+            let Ok(entries) = fs::read_dir(library.join(location)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let is_candidate = if location == "Preferences" {
+                    // Only app preference files; `ByHost` and other folders are managed by macOS.
+                    entry.path().is_file() && name.ends_with(".plist")
+                } else {
+                    true
+                };
+                if is_candidate && !self.owners.owns(&name) {
+                    paths.push(entry.path());
+                }
+            }
+        }
+        paths
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+
+    /// Every orphaned entry is listed on its own row, so it can be checked individually.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        path.to_path_buf()
+    }
+}
+
+/// The identities of the installed apps, normalized for matching against data folder names.
+pub struct AppOwners {
+    /// Lowercased bundle identifiers, e.g. "com.microsoft.vscode".
+    bundle_ids: Vec<String>,
+    /// Names, bundle names and executables reduced to lowercase alphanumerics, e.g. "visualstudiocode".
+    names: Vec<String>,
+    /// Lowercased components of the bundle identifiers, e.g. "microsoft" and "vscode".
+    id_components: Vec<String>,
+}
+
+impl AppOwners {
+    pub fn new(apps: &[InstalledApp]) -> Self {
+        let bundle_ids: Vec<String> = apps
+            .iter()
+            .filter_map(|app| app.bundle_id.as_ref())
+            .map(|id| id.to_lowercase())
+            .collect();
+        let names = apps
+            .iter()
+            .flat_map(|app| {
+                std::iter::once(&app.name)
+                    .chain(&app.bundle_names)
+                    .chain(&app.executable)
+                    .map(|name| normalize_name(name))
+            })
+            .filter(|name| !name.is_empty())
+            .collect();
+        let id_components = bundle_ids
+            .iter()
+            .flat_map(|id| id.split('.').skip(1).map(str::to_string))
+            .filter(|component| component.len() >= 3)
+            .collect();
+        AppOwners {
+            bundle_ids,
+            names,
+            id_components,
+        }
+    }
+
+    /// Returns `true` if the data entry `entry_name` (a folder or file name in `~/Library/...`)
+    /// belongs to an installed app, or to macOS itself.
+    ///
+    /// Reverse-DNS names are matched against bundle identifiers, including helpers of an app
+    /// (`com.foo.app.helper`) and, for group containers, any app of the same vendor. Other names
+    /// are matched against app names, bundle names and executables.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use osx::core::cleaners::orphaned_app_data::AppOwners;
+    /// use osx::core::installed_apps::InstalledApp;
+    /// use std::path::PathBuf;
+    ///
+    /// let code = InstalledApp {
+    ///     name: "Visual Studio Code".to_string(),
+    ///     path: PathBuf::from("/Applications/Visual Studio Code.app"),
+    ///     bundle_id: Some("com.microsoft.VSCode".to_string()),
+    ///     bundle_names: vec!["Code".to_string()],
+    ///     executable: Some("Electron".to_string()),
+    ///     version: None,
+    /// };
+    /// let owners = AppOwners::new(&[code]);
+    ///
+    /// assert!(owners.owns("Code"));
+    /// assert!(owners.owns("com.microsoft.VSCode.plist"));
+    /// assert!(owners.owns("com.microsoft.VSCode.ShipIt"));
+    /// assert!(owners.owns("UBF8T346G9.com.microsoft.teams"));
+    /// assert!(owners.owns("com.apple.Safari"));
+    /// assert!(owners.owns("CloudDocs"));
+    /// assert!(!owners.owns("com.tinyspeck.slackmacgap"));
+    /// assert!(!owners.owns("Slack"));
+    /// ```
+    pub fn owns(&self, entry_name: &str) -> bool {
+        let name = entry_name.strip_suffix(".plist").unwrap_or(entry_name);
+        let lower = name.to_lowercase();
+        if name.starts_with('.') || lower.contains("apple") || SYSTEM_DATA_NAMES.contains(&name) {
+            return true;
+        }
+
+        // Group containers are named `group.<id>` or `<TEAM ID>.<id>`.
+        let (id, is_group) = match (lower.strip_prefix("group."), strip_team_id(name)) {
+            (Some(id), _) => (id.to_string(), true),
+            (None, Some(id)) => (id.to_lowercase(), true),
+            (None, None) => (lower, false),
+        };
+        let id = id.as_str();
+
+        if is_reverse_dns(id) {
+            self.bundle_ids.iter().any(|bundle_id| {
+                bundle_id == id
+                    || is_id_prefix(bundle_id, id)
+                    || is_id_prefix(id, bundle_id)
+                    || (is_group && vendor(bundle_id) == vendor(id))
+            })
+        } else {
+            // Plain names, such as `Application Support/Code` or the rest of a group container name.
+            let normalized = normalize_name(id);
+            if normalized.len() < 3 {
+                return true;
+            }
+            self.names.iter().any(|app_name| {
+                app_name == &normalized
+                    || (app_name.len() >= 3 && (app_name.starts_with(&normalized) || normalized.starts_with(app_name.as_str())))
+            }) || self.id_components.contains(&normalized)
+        }
+    }
+}
+
+/// Reduces a name to lowercase alphanumerics, so "Visual Studio Code" matches "visual-studio-code".
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Strips the 10-character Apple team identifier prefix of a group container name, if any.
+fn strip_team_id(name: &str) -> Option<&str> {
+    let (team, rest) = name.split_once('.')?;
+    let is_team_id = team.len() == 10
+        && team.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    is_team_id.then_some(rest)
+}
+
+/// Returns `true` if `name` looks like a reverse-DNS identifier, e.g. `com.example.app`.
+fn is_reverse_dns(name: &str) -> bool {
+    name.split('.').count() >= 3 && !name.contains(' ')
+}
+
+/// Returns `true` if `id` is `prefix` followed by more components, e.g. `com.foo.app.helper`.
+fn is_id_prefix(id: &str, prefix: &str) -> bool {
+    id.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Returns the vendor part of an identifier, e.g. `com.microsoft` for `com.microsoft.teams`.
+fn vendor(id: &str) -> String {
+    id.split('.').take(2).collect::<Vec<_>>().join(".")
+}
@@ -0,0 +1,39 @@
+use super::Cleaner;
+use crate::core::toolchains::{inventory_toolchains, project_roots};
+use std::path::PathBuf;
+
+/// Represents a report-only cleaner for developer toolchain versions that are neither the default
+/// of their version manager nor pinned by a project.
+///
+/// The reported versions are the ones `osx toolchains --prune` would remove.
+pub struct ToolchainsCleaner;
+
+impl ToolchainsCleaner {
+    pub fn new() -> Self {
+        ToolchainsCleaner // This simply returns an instance of the struct
+    }
+}
+
+impl Default for ToolchainsCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for ToolchainsCleaner {
+    fn name(&self) -> &str {
+        "Unused Toolchains"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        inventory_toolchains(&project_roots())
+            .into_iter()
+            .filter(|toolchain| toolchain.keep_reason.is_none())
+            .map(|toolchain| toolchain.path)
+            .collect()
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+}
@@ -1,14 +1,54 @@
-use crate::core::cleaners::Cleaner;
+use crate::core::cleaners::{Cleaner, SkippedEntry};
+use crate::log_warn;
+use crate::utils::filesystem::{current_uid, invoking_uid, volume_flags};
+use colored::Colorize;
 use glob::glob;
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Environment variable that, when running as root, empties the trash of every user on mounted volumes.
+pub const TRASH_ALL_USERS_ENV: &str = "OSX_TRASH_ALL_USERS";
 
 /// Represents a cleaner for Trash bins (user's and mounted volumes).
-pub struct TrashCleaner;
+///
+/// Only the contents of the trash folders are removed, never the folders themselves. On mounted
+/// volumes, macOS keeps one trash per user in `.Trashes/<uid>`: only the invoking user's is emptied,
+/// or every user's when running as root with `OSX_TRASH_ALL_USERS` set. Read-only and network volumes
+/// are skipped. Items are summarized per volume.
+pub struct TrashCleaner {
+    /// Volume trashes that were skipped during the last `find_paths`, with the reason.
+    skipped: Mutex<Vec<SkippedEntry>>,
+}
 
 impl TrashCleaner {
     pub fn new() -> Self {
-        TrashCleaner // This simply returns an instance of the struct
+        TrashCleaner {
+            skipped: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the per-user trash folders of a volume's `.Trashes` folder that should be emptied.
+    fn user_trashes(trashes: &Path, all_users: bool) -> Vec<PathBuf> {
+        if !all_users {
+            // Under `sudo`, the trash of the user who ran the command, like `~/.Trash`, not root's.
+            return vec![trashes.join(invoking_uid().to_string())];
+        }
+        match fs::read_dir(trashes) {
+            Ok(entries) => entries
+                .flatten()
+                .filter(|e| e.file_name().to_str().is_some_and(|n| n.parse::<u32>().is_ok()))
+                .map(|e| e.path())
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl Default for TrashCleaner {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -18,15 +58,59 @@ impl Cleaner for TrashCleaner {
     }
 
     fn find_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
+        let mut trash_dirs = Vec::new();
         if let Ok(home) = env::var("HOME") {
-            paths.push(PathBuf::from(format!("{}/.Trash", home)));
+            trash_dirs.push(PathBuf::from(format!("{}/.Trash", home)));
         }
+
+        let all_users = env::var(TRASH_ALL_USERS_ENV).is_ok();
+        if all_users && current_uid() != 0 {
+            log_warn!("{} is only honored when running as root.", TRASH_ALL_USERS_ENV);
+        }
+        let all_users = all_users && current_uid() == 0;
+
+        let mut skipped = Vec::new();
         if let Ok(volumes) = glob("/Volumes/*/.Trashes") {
-            for entry in volumes.flatten() {
-                paths.push(entry);
+            for trashes in volumes.flatten() {
+                let reason = match volume_flags(&trashes) {
+                    Ok(flags) if flags.read_only => Some("Read-only volume".to_string()),
+                    Ok(flags) if !flags.local => Some("Network volume".to_string()),
+                    Ok(_) => None,
+                    Err(e) => Some(format!("Could not inspect volume: {}", e)),
+                };
+                match reason {
+                    Some(reason) => skipped.push(SkippedEntry {
+                        path: trashes.display().to_string(),
+                        reason,
+                    }),
+                    None => trash_dirs.extend(Self::user_trashes(&trashes, all_users)),
+                }
             }
         }
-        paths
+        *self.skipped.lock().unwrap() = skipped;
+
+        // Empty the trash folders, but keep them: macOS expects them to exist.
+        trash_dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|e| e.path()))
+            .collect()
+    }
+
+    fn skipped_paths(&self) -> Vec<SkippedEntry> {
+        self.skipped.lock().unwrap().drain(..).collect()
+    }
+
+    /// Trashed items are summarized per volume: the volume root for mounted volumes,
+    /// and the user's `.Trash` folder for the boot volume.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        if path.starts_with("/Volumes") {
+            path.ancestors()
+                .find(|p| p.parent() == Some(Path::new("/Volumes")))
+                .unwrap_or(path)
+                .to_path_buf()
+        } else {
+            path.parent().unwrap_or(path).to_path_buf()
+        }
     }
-}
\ No newline at end of file
+}
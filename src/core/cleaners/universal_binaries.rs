@@ -0,0 +1,75 @@
+use crate::core::cleaners::Cleaner;
+use crate::core::macho::{FatArch, find_thinnable_binaries, host_cpu_type, read_fat_archs, reclaimable_bytes};
+use crate::log_info;
+use colored::Colorize;
+use glob::glob;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Represents a report-only cleaner for architecture slices of universal binaries that do not
+/// match the host, e.g. x86_64 slices of apps on Apple Silicon.
+///
+/// Only the bytes of the other slices are reported, per app. Thinning rewrites the binaries of a
+/// signed bundle, so it is left to an explicit `osx thin-binaries --strip`.
+pub struct UniversalBinariesCleaner {
+    host: Option<u32>,
+}
+
+impl UniversalBinariesCleaner {
+    pub fn new() -> Self {
+        UniversalBinariesCleaner { host: host_cpu_type() }
+    }
+}
+
+impl Default for UniversalBinariesCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for UniversalBinariesCleaner {
+    fn name(&self) -> &str {
+        "Universal Binaries"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let (Some(host), Ok(apps)) = (self.host, glob("/Applications/*.app")) else {
+            return Vec::new();
+        };
+        let mut paths = Vec::new();
+        for app in apps.flatten() {
+            let binaries = find_thinnable_binaries(&app, host);
+            if let Some((_, archs)) = binaries.first() {
+                log_info!(
+                    "🧬 Universal app: {} ({})",
+                    app.display().to_string().white().dimmed(),
+                    archs.iter().map(FatArch::name).collect::<Vec<_>>().join(", ").bright_white()
+                );
+            }
+            paths.extend(binaries.into_iter().map(|(path, _)| path));
+        }
+        paths
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+
+    /// Binaries are summarized per app bundle.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        path.ancestors()
+            .find(|p| p.extension().is_some_and(|e| e == "app"))
+            .unwrap_or(path)
+            .to_path_buf()
+    }
+
+    /// Only the slices for other architectures would be freed, not the whole binary.
+    fn path_size(&self, path: &Path) -> io::Result<u64> {
+        let host = self.host.unwrap_or_default();
+        Ok(read_fat_archs(path)?
+            .and_then(|archs| reclaimable_bytes(&archs, host))
+            .unwrap_or(0))
+    }
+}
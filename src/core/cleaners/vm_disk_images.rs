@@ -0,0 +1,177 @@
+use crate::core::cleaners::{Cleaner, format_bytes};
+use crate::log_info;
+use colored::Colorize;
+use glob::glob;
+use std::{
+    env, fs, io,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// A kind of VM or container disk image, found by a glob pattern relative to the home directory.
+pub struct VmDiskImageKind {
+    /// The tool that owns the image.
+    pub owner: &'static str,
+    /// Glob pattern of the image (a file, or a bundle directory for VM packages).
+    pub pattern: &'static str,
+    /// How to reclaim the space with the owning tool.
+    pub hint: &'static str,
+}
+
+/// Disk images of the common VM and container tools.
+pub const VM_DISK_IMAGE_KINDS: &[VmDiskImageKind] = &[
+    VmDiskImageKind {
+        owner: "Docker Desktop",
+        pattern: "Library/Containers/com.docker.docker/Data/vms/*/data/Docker.raw",
+        hint: "run `docker system prune`, or lower the disk limit in Docker Desktop settings",
+    },
+    VmDiskImageKind {
+        owner: "Docker Desktop",
+        pattern: "Library/Containers/com.docker.docker/Data/vms/*/data/Docker.qcow2",
+        hint: "run `docker system prune`, or lower the disk limit in Docker Desktop settings",
+    },
+    VmDiskImageKind {
+        owner: "Lima",
+        pattern: ".lima/*/diffdisk",
+        hint: "run `limactl prune`, or `limactl delete <instance>`",
+    },
+    VmDiskImageKind {
+        owner: "Colima",
+        pattern: ".colima/_lima/*/diffdisk",
+        hint: "run `docker system prune` inside Colima, or `colima delete <profile>`",
+    },
+    VmDiskImageKind {
+        owner: "OrbStack",
+        pattern: "Library/Group Containers/*.dev.orbstack/data/data.img",
+        hint: "run `docker system prune`, or delete machines with `orb delete <machine>`",
+    },
+    VmDiskImageKind {
+        owner: "UTM",
+        pattern: "Library/Containers/com.utmapp.UTM/Data/Documents/*.utm",
+        hint: "delete the VM from UTM, or reclaim space with \"Reclaim Space\" in its drive settings",
+    },
+    VmDiskImageKind {
+        owner: "Parallels Desktop",
+        pattern: "Parallels/*.pvm",
+        hint: "delete the VM from Parallels Control Center, or use \"Free Up Disk Space\"",
+    },
+    VmDiskImageKind {
+        owner: "VirtualBox",
+        pattern: "VirtualBox VMs/*/*.vdi",
+        hint: "delete the VM from VirtualBox, or compact the disk with `VBoxManage modifymedium --compact`",
+    },
+    VmDiskImageKind {
+        owner: "VMware Fusion",
+        pattern: "Virtual Machines.localized/*.vmwarevm",
+        hint: "delete the VM from VMware Fusion, or use \"Clean Up Virtual Machine\"",
+    },
+];
+
+/// Represents a report-only analyzer for VM and container disk images.
+///
+/// These images are sparse: their apparent size (the size of the virtual disk) is often far larger
+/// than the space they use. Each image is logged with its owning tool, both sizes and how to reclaim
+/// the space; the allocated size is what is reported. Deleting an image would destroy every container
+/// or VM in it, so deletion is left to the owning tool.
+pub struct VmDiskImagesCleaner;
+
+impl VmDiskImagesCleaner {
+    pub fn new() -> Self {
+        VmDiskImagesCleaner // This simply returns an instance of the struct
+    }
+}
+
+impl Default for VmDiskImagesCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleaner for VmDiskImagesCleaner {
+    fn name(&self) -> &str {
+        "VM Disk Images"
+    }
+
+    fn find_paths(&self) -> Vec<PathBuf> {
+        let home = env::var("HOME").unwrap_or_default();
+        let home = glob::Pattern::escape(&home);
+        let mut paths = Vec::new();
+        for kind in VM_DISK_IMAGE_KINDS {
+            let Ok(images) = glob(&format!("{}/{}", home, kind.pattern)) else {
+                continue;
+            };
+            for image in images.flatten() {
+                let (Ok(apparent), Ok(allocated)) = (apparent_size(&image), allocated_size(&image)) else {
+                    continue;
+                };
+                log_info!(
+                    "💽 {} disk image: {} ({} apparent, {} allocated) - to reclaim space, {}",
+                    kind.owner.bright_white(),
+                    image.display().to_string().white().dimmed(),
+                    format_bytes(apparent),
+                    format_bytes(allocated).bright_green(),
+                    kind.hint
+                );
+                paths.push(image);
+            }
+        }
+        paths
+    }
+
+    fn is_report_only(&self) -> bool {
+        true
+    }
+
+    /// Every image is listed on its own row.
+    fn summary_path(&self, path: &Path) -> PathBuf {
+        path.to_path_buf()
+    }
+
+    /// Sparse images are reported with the space they actually use on disk.
+    fn path_size(&self, path: &Path) -> io::Result<u64> {
+        allocated_size(path)
+    }
+}
+
+/// Returns the apparent size of a file or directory: the sum of the file lengths.
+pub fn apparent_size(path: &Path) -> io::Result<u64> {
+    sum_metadata(path, |m| m.len())
+}
+
+/// Returns the space allocated on disk for a file or directory. For sparse files this is
+/// smaller than the apparent size, since holes take no space.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::cleaners::vm_disk_images::{allocated_size, apparent_size};
+/// use std::fs::File;
+///
+/// let path = std::env::temp_dir().join(format!("osx-sparse-doc-{}", std::process::id()));
+/// // A 1 GB sparse file, like a freshly created VM disk.
+/// File::create(&path).unwrap().set_len(1024 * 1024 * 1024).unwrap();
+///
+/// assert_eq!(apparent_size(&path).unwrap(), 1024 * 1024 * 1024);
+/// assert!(allocated_size(&path).unwrap() < 1024 * 1024);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn allocated_size(path: &Path) -> io::Result<u64> {
+    // `st_blocks` is always counted in 512-byte units, whatever the file system's block size.
+    sum_metadata(path, |m| m.blocks() * 512)
+}
+
+/// Sums `size` over the file at `path`, or over every file below it if it is a directory.
+fn sum_metadata(path: &Path, size: impl Fn(&fs::Metadata) -> u64) -> io::Result<u64> {
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(size(&metadata));
+    }
+    Ok(WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| size(&m))
+        .sum())
+}
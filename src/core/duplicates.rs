@@ -0,0 +1,306 @@
+// This file implements duplicate file detection for the `duplicates` subcommand and the
+// report-only `DuplicateFilesCleaner`.
+// Files are grouped by size first, then by a hash of their first bytes, and only then by a
+// hash of their full contents, so most files are never read completely.
+
+use crate::core::cleaners::format_bytes;
+use crate::utils::filesystem::remove_path;
+use crate::{log_debug, log_info, log_warn};
+use clap::ValueEnum;
+use colored::Colorize;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, Read},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tabled::{Table, Tabled, settings::Style};
+use walkdir::WalkDir;
+
+/// Number of bytes hashed for the partial hash pass.
+const PARTIAL_HASH_BYTES: u64 = 16 * 1024;
+
+/// A file that is part of a duplicate set.
+#[derive(Clone)]
+pub struct DuplicateFile {
+    pub path: PathBuf,
+    pub modified: SystemTime,
+}
+
+/// A group of files with identical contents.
+pub struct DuplicateSet {
+    /// Size in bytes of each file in the set.
+    pub size: u64,
+    /// The identical files, sorted by path.
+    pub files: Vec<DuplicateFile>,
+}
+
+impl DuplicateSet {
+    /// Bytes that would be freed by keeping a single copy of the file.
+    pub fn reclaimable_bytes(&self) -> u64 {
+        self.size * (self.files.len() as u64).saturating_sub(1)
+    }
+
+    /// Returns the index of the copy to keep according to `strategy`.
+    ///
+    /// With `KeepStrategy::Path`, the first copy located under `prefer` is kept; when no copy
+    /// matches, the oldest copy is kept instead.
+    pub fn keeper_index(&self, strategy: KeepStrategy, prefer: Option<&Path>) -> usize {
+        let oldest = || {
+            self.files
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, f)| f.modified)
+                .map_or(0, |(i, _)| i)
+        };
+        match strategy {
+            KeepStrategy::Oldest => oldest(),
+            KeepStrategy::Newest => self
+                .files
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, f)| f.modified)
+                .map_or(0, |(i, _)| i),
+            KeepStrategy::Path => prefer
+                .and_then(|prefer| self.files.iter().position(|f| f.path.starts_with(prefer)))
+                .unwrap_or_else(oldest),
+        }
+    }
+}
+
+/// Which copy of a duplicate set to keep when removing the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum KeepStrategy {
+    /// Keep the copy with the oldest modification time.
+    Oldest,
+    /// Keep the copy with the newest modification time.
+    Newest,
+    /// Keep the copy located under the `--prefer` directory.
+    Path,
+}
+
+/// Represents a row of the duplicate sets table.
+#[derive(Tabled)]
+struct DuplicateEntry {
+    #[tabled(rename = "Set")]
+    set: String,
+    #[tabled(rename = "Path")]
+    path: String,
+    #[tabled(rename = "Size")]
+    size: String,
+    #[tabled(rename = "Action")]
+    action: String,
+}
+
+/// Finds sets of files with identical contents under `roots`.
+///
+/// Files smaller than `min_size` bytes, symlinks and additional hard links to an already
+/// seen file are ignored. Sets are returned sorted by reclaimable bytes, largest first.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::duplicates::find_duplicates;
+/// use std::fs;
+///
+/// let root = std::env::temp_dir().join(format!("osx-duplicates-doc-{}", std::process::id()));
+/// fs::create_dir_all(root.join("nested")).unwrap();
+/// fs::write(root.join("a.txt"), "same contents").unwrap();
+/// fs::write(root.join("nested/b.txt"), "same contents").unwrap();
+/// fs::write(root.join("c.txt"), "same length!!").unwrap();
+///
+/// let sets = find_duplicates(&[root.clone()], 1);
+/// assert_eq!(sets.len(), 1);
+/// assert_eq!(sets[0].files.len(), 2);
+/// assert_eq!(sets[0].reclaimable_bytes(), 13);
+/// fs::remove_dir_all(&root).unwrap();
+/// ```
+pub fn find_duplicates(roots: &[PathBuf], min_size: u64) -> Vec<DuplicateSet> {
+    // Pass 1: group candidate files by size.
+    let mut by_size: HashMap<u64, Vec<DuplicateFile>> = HashMap::new();
+    let mut seen_inodes: HashSet<(u64, u64)> = HashSet::new();
+    for root in roots {
+        if !root.exists() {
+            log_debug!("Skipping missing duplicate scan root: {}", root.display());
+            continue;
+        }
+        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    log_warn!("Failed to read metadata for {}: {}", entry.path().display(), e);
+                    continue;
+                }
+            };
+            // Hard links share their data, so removing one of them frees nothing.
+            if metadata.len() < min_size || !seen_inodes.insert((metadata.dev(), metadata.ino())) {
+                continue;
+            }
+            by_size.entry(metadata.len()).or_default().push(DuplicateFile {
+                path: entry.path().to_path_buf(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+    }
+
+    let candidates: Vec<(u64, Vec<DuplicateFile>)> =
+        by_size.into_iter().filter(|(_, files)| files.len() > 1).collect();
+    log_debug!("Found {} file sizes shared by more than one file.", candidates.len());
+
+    // Pass 2 and 3: split each size group by partial hash, then by full hash.
+    let mut sets: Vec<DuplicateSet> = candidates
+        .into_par_iter()
+        .flat_map_iter(|(size, files)| {
+            group_by_hash(files, |p| hash_file(p, Some(PARTIAL_HASH_BYTES)))
+                .into_iter()
+                .flat_map(|files| {
+                    // Files no larger than the partial hash window were already hashed completely.
+                    if size <= PARTIAL_HASH_BYTES {
+                        vec![files]
+                    } else {
+                        group_by_hash(files, |p| hash_file(p, None))
+                    }
+                })
+                .map(move |mut files| {
+                    files.sort_by(|a, b| a.path.cmp(&b.path));
+                    DuplicateSet { size, files }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    sets.sort_by(|a, b| {
+        b.reclaimable_bytes()
+            .cmp(&a.reclaimable_bytes())
+            .then_with(|| a.files[0].path.cmp(&b.files[0].path))
+    });
+    sets
+}
+
+/// Splits `files` into groups sharing the same hash, dropping groups with a single file.
+fn group_by_hash(
+    files: Vec<DuplicateFile>,
+    hasher: impl Fn(&Path) -> io::Result<Vec<u8>>,
+) -> Vec<Vec<DuplicateFile>> {
+    let mut groups: HashMap<Vec<u8>, Vec<DuplicateFile>> = HashMap::new();
+    for file in files {
+        match hasher(&file.path) {
+            Ok(hash) => groups.entry(hash).or_default().push(file),
+            Err(e) => log_warn!("Failed to hash {}: {}", file.path.display(), e),
+        }
+    }
+    groups.into_values().filter(|group| group.len() > 1).collect()
+}
+
+/// Computes the SHA-256 of a file, limited to its first `limit` bytes when given.
+fn hash_file(path: &Path, limit: Option<u64>) -> io::Result<Vec<u8>> {
+    let file = File::open(path)?;
+    let mut reader: Box<dyn Read> = match limit {
+        Some(limit) => Box::new(file.take(limit)),
+        None => Box::new(file),
+    };
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Runs the `duplicates` subcommand.
+///
+/// Lists every duplicate set found under `roots` with its reclaimable bytes. When `keep` is
+/// given, all copies but one per set are removed through `remove_path` (simulated in `dry_run`).
+pub fn run_duplicates(
+    roots: &[PathBuf],
+    min_size: u64,
+    keep: Option<KeepStrategy>,
+    prefer: Option<&Path>,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for root in roots {
+        log_info!("🔍 Scanning for duplicates: {}", root.display().to_string().blue());
+    }
+    let sets = find_duplicates(roots, min_size);
+    if sets.is_empty() {
+        log_info!("{}", "No duplicate files found.".bright_white());
+        return Ok(());
+    }
+
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+    let mut total_reclaimable = 0;
+    let mut total_removed = 0;
+    for (index, set) in sets.iter().enumerate() {
+        total_reclaimable += set.reclaimable_bytes();
+        let keeper = keep.map(|strategy| set.keeper_index(strategy, prefer));
+
+        for (file_index, file) in set.files.iter().enumerate() {
+            let action = match keeper {
+                None => String::new(),
+                Some(k) if k == file_index => "Keep".to_string(),
+                Some(_) => match remove_path(&file.path, dry_run) {
+                    Ok(_) => {
+                        total_removed += set.size;
+                        if dry_run { "Would delete" } else { "Deleted" }.to_string()
+                    }
+                    Err(e) => {
+                        log_warn!("❌ Failed to delete {}: {}", file.path.display(), e);
+                        failures.push(file.path.display().to_string());
+                        format!("Failed: {}", e)
+                    }
+                },
+            };
+            entries.push(DuplicateEntry {
+                set: if file_index == 0 { format!("#{}", index + 1) } else { String::new() },
+                path: file.path.display().to_string(),
+                size: format_bytes(set.size),
+                action,
+            });
+        }
+    }
+
+    entries.push(DuplicateEntry {
+        set: String::new(),
+        path: "Total Reclaimable".to_string(),
+        size: format_bytes(total_reclaimable),
+        action: String::new(),
+    });
+
+    println!(
+        "\n{}\n",
+        format!("🗂️  Duplicate Files ({} sets)", sets.len()).bold().underline().purple()
+    );
+    println!("{}", Table::new(&entries).with(Style::modern()));
+
+    eprintln!();
+    match keep {
+        None => log_info!(
+            "🧠 Reclaimable by keeping one copy per set: {} (use --keep to remove duplicates)",
+            format_bytes(total_reclaimable).bright_green().bold()
+        ),
+        Some(_) if dry_run => log_info!(
+            "🧠 Estimated space to free: {}",
+            format_bytes(total_removed).bright_green().bold()
+        ),
+        Some(_) => log_info!(
+            "✔ Total space freed: {}",
+            format_bytes(total_removed).bright_green().bold()
+        ),
+    }
+    if !failures.is_empty() {
+        return Err(format!("{} duplicate(s) could not be deleted", failures.len()).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,273 @@
+// This file lets the uninstaller hand Homebrew-managed tools over to `brew uninstall`. Removing a keg
+// by hand leaves Homebrew's bookkeeping (`opt/` links, `var/homebrew/linked`, dependencies) behind,
+// so a tool whose binary resolves into a `Cellar` or `Caskroom` is uninstalled through `brew` instead.
+
+use crate::utils::command::CommandRunner;
+use crate::{log_debug, log_info};
+use colored::Colorize;
+use glob::glob;
+use std::{
+    env, fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// Default Homebrew prefixes: Apple Silicon, then Intel.
+const HOMEBREW_PREFIXES: &[&str] = &["/opt/homebrew", "/usr/local"];
+/// Maximum number of symlinks followed from a binary to its package.
+const MAX_LINK_HOPS: usize = 16;
+
+/// A formula or cask installed by Homebrew.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HomebrewPackage {
+    /// Formula or cask name, e.g. `ripgrep` for the `rg` binary.
+    pub name: String,
+    pub cask: bool,
+    /// Folder of the package in the `Cellar` or `Caskroom`.
+    pub path: PathBuf,
+}
+
+impl HomebrewPackage {
+    /// Returns the package owning `path` if it lies in a Homebrew `Cellar` or `Caskroom`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use osx::core::homebrew::HomebrewPackage;
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let package = HomebrewPackage::from_path(Path::new("/opt/homebrew/Cellar/ripgrep/14.1.0/bin/rg")).unwrap();
+    /// assert_eq!(package.name, "ripgrep");
+    /// assert!(!package.cask);
+    /// assert_eq!(package.path, PathBuf::from("/opt/homebrew/Cellar/ripgrep"));
+    ///
+    /// let package = HomebrewPackage::from_path(Path::new("/usr/local/Caskroom/docker/4.30.0/docker")).unwrap();
+    /// assert_eq!(package.name, "docker");
+    /// assert!(package.cask);
+    ///
+    /// assert!(HomebrewPackage::from_path(Path::new("/usr/local/bin/node")).is_none());
+    /// ```
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let components: Vec<Component> = path.components().collect();
+        components.windows(2).enumerate().find_map(|(i, pair)| {
+            let (Component::Normal(dir), Component::Normal(name)) = (pair[0], pair[1]) else {
+                return None;
+            };
+            let cask = match dir.to_str()? {
+                "Cellar" => false,
+                "Caskroom" => true,
+                _ => return None,
+            };
+            Some(HomebrewPackage {
+                name: name.to_string_lossy().to_string(),
+                cask,
+                path: components[..i + 2].iter().collect(),
+            })
+        })
+    }
+}
+
+/// Returns the Homebrew prefixes to look into: `HOMEBREW_PREFIX` if set, then the default ones.
+fn homebrew_prefixes() -> Vec<PathBuf> {
+    let mut prefixes: Vec<PathBuf> = env::var("HOMEBREW_PREFIX").map(PathBuf::from).into_iter().collect();
+    prefixes.extend(HOMEBREW_PREFIXES.iter().map(PathBuf::from));
+    prefixes.dedup();
+    prefixes
+}
+
+/// Follows the symlink chain starting at `link` one hop at a time, and returns the package of the first
+/// path that lies in a `Cellar` or `Caskroom`.
+///
+/// Resolving the whole chain at once isn't enough: cask binaries end up inside the app bundle they were
+/// installed with (e.g. `/Applications/Visual Studio Code.app`), past the `Caskroom` hop.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::homebrew::package_of_link;
+/// use std::{env, fs, os::unix::fs::symlink};
+///
+/// let prefix = env::temp_dir().join("osx-doctest-homebrew-links");
+/// let _ = fs::remove_dir_all(&prefix);
+/// let app_binary = prefix.join("Applications/Visual Studio Code.app/Contents/Resources/app/bin/code");
+/// fs::create_dir_all(app_binary.parent().unwrap()).unwrap();
+/// fs::write(&app_binary, "").unwrap();
+/// let staged = prefix.join("Caskroom/visual-studio-code/1.89.1/code");
+/// fs::create_dir_all(staged.parent().unwrap()).unwrap();
+/// symlink(&app_binary, &staged).unwrap();
+/// fs::create_dir_all(prefix.join("bin")).unwrap();
+/// symlink("../Caskroom/visual-studio-code/1.89.1/code", prefix.join("bin/code")).unwrap();
+///
+/// let package = package_of_link(&prefix.join("bin/code")).unwrap();
+/// assert_eq!(package.name, "visual-studio-code");
+/// assert!(package.cask);
+/// assert_eq!(package.path, prefix.join("Caskroom/visual-studio-code"));
+///
+/// assert!(package_of_link(&app_binary).is_none());
+/// ```
+pub fn package_of_link(link: &Path) -> Option<HomebrewPackage> {
+    let mut current = link.to_path_buf();
+    // Bounded, in case of a symlink loop.
+    for _ in 0..MAX_LINK_HOPS {
+        if let Some(package) = HomebrewPackage::from_path(&current) {
+            return Some(package);
+        }
+        let target = fs::read_link(&current).ok()?;
+        current = normalize(&current.parent().unwrap_or(Path::new("/")).join(target));
+    }
+    None
+}
+
+/// Resolves the `.` and `..` components of `path` lexically, without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Finds the Homebrew package providing the tool `name`, if any.
+///
+/// The tool's binary in `<prefix>/bin` is followed through its symlinks into the `Cellar` or `Caskroom`
+/// (see `package_of_link`); failing that, a formula or cask named after the tool is looked for.
+pub fn find_homebrew_package(name: &str) -> Option<HomebrewPackage> {
+    let prefixes = homebrew_prefixes();
+    let from_binary = prefixes
+        .iter()
+        .find_map(|prefix| package_of_link(&prefix.join("bin").join(name)));
+    let package = from_binary.or_else(|| {
+        prefixes.iter().find_map(|prefix| {
+            [("Cellar", false), ("Caskroom", true)].iter().find_map(|(dir, cask)| {
+                let path = prefix.join(dir).join(name);
+                path.is_dir().then(|| HomebrewPackage {
+                    name: name.to_string(),
+                    cask: *cask,
+                    path,
+                })
+            })
+        })
+    });
+    if let Some(package) = &package {
+        log_debug!("'{}' is managed by Homebrew as {}", name, package.name.bright_white());
+    }
+    package
+}
+
+/// Returns the cask that installed the app bundle `bundle` (e.g. `/Applications/Slack.app`), if any.
+///
+/// Homebrew moves a cask's app out of the `Caskroom`, so the cask is recognized by its metadata instead:
+/// the cask definition kept in `Caskroom/<token>/.metadata` names the app it installed.
+pub fn cask_of_app(bundle: &Path) -> Option<HomebrewPackage> {
+    let app = bundle.file_name()?.to_str()?;
+    let cask = homebrew_prefixes()
+        .iter()
+        .find_map(|prefix| cask_installing(&prefix.join("Caskroom"), app));
+    if let Some(cask) = &cask {
+        log_debug!("{} was installed by the cask {}", bundle.display(), cask.name.bright_white());
+    }
+    cask
+}
+
+/// Returns the cask of `caskroom` whose definition installs the app `app` (e.g. `Slack.app`).
+fn cask_installing(caskroom: &Path, app: &str) -> Option<HomebrewPackage> {
+    // Definitions are stored as `.metadata/<version>/<timestamp>/Casks/<token>.json` (or `.rb`).
+    let pattern = caskroom.join("*/.metadata/*/*/Casks/*");
+    let needle = format!("\"{}\"", app);
+    glob(&pattern.to_string_lossy())
+        .ok()?
+        .flatten()
+        .find(|definition| fs::read_to_string(definition).is_ok_and(|text| text.contains(&needle)))
+        .and_then(|definition| {
+            let token = definition.strip_prefix(caskroom).ok()?.components().next()?;
+            Some(HomebrewPackage {
+                name: token.as_os_str().to_string_lossy().to_string(),
+                cask: true,
+                path: caskroom.join(token),
+            })
+        })
+}
+
+/// Uninstalls `package` with `brew uninstall`, then removes the dependencies nothing needs anymore
+/// with `brew autoremove` when `autoremove` is set. Nothing is run in `dry_run`.
+pub fn brew_uninstall(
+    runner: &dyn CommandRunner,
+    package: &HomebrewPackage,
+    autoremove: bool,
+    dry_run: bool,
+) -> io::Result<()> {
+    let mut args = vec!["uninstall"];
+    if package.cask {
+        args.push("--cask");
+    }
+    args.push(&package.name);
+    if dry_run {
+        log_info!("Would run: {}", format!("brew {}", args.join(" ")).bright_white());
+        if autoremove {
+            log_info!("Would run: {}", "brew autoremove".bright_white());
+        }
+        return Ok(());
+    }
+
+    log_info!("Running: {}", format!("brew {}", args.join(" ")).bright_white());
+    runner.run_checked("brew", &args)?;
+    if autoremove {
+        log_info!("Running: {}", "brew autoremove".bright_white());
+        runner.run_checked("brew", &["autoremove"])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::command::testing::RecordingRunner;
+
+    #[test]
+    fn brew_uninstall_removes_casks_then_autoremoves() {
+        let runner = RecordingRunner::succeeding();
+        let cask = HomebrewPackage {
+            name: "docker".to_string(),
+            cask: true,
+            path: PathBuf::from("/opt/homebrew/Caskroom/docker"),
+        };
+        brew_uninstall(&runner, &cask, true, true).unwrap();
+        assert!(runner.commands().is_empty());
+
+        brew_uninstall(&runner, &cask, true, false).unwrap();
+        assert_eq!(runner.commands(), vec!["brew uninstall --cask docker", "brew autoremove"]);
+    }
+
+    #[test]
+    fn apps_are_recognized_by_the_cask_definition() {
+        let caskroom = std::env::temp_dir().join(format!("osx-test-caskroom-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&caskroom);
+        let casks = caskroom.join("slack/.metadata/4.38.125/20240501120000.000/Casks");
+        fs::create_dir_all(&casks).unwrap();
+        fs::write(casks.join("slack.json"), r#"{"token":"slack","artifacts":[{"app":["Slack.app"]}]}"#).unwrap();
+
+        let cask = cask_installing(&caskroom, "Slack.app").unwrap();
+        assert_eq!(cask.name, "slack");
+        assert!(cask.cask);
+        assert_eq!(cask.path, caskroom.join("slack"));
+        assert!(cask_installing(&caskroom, "Slack Beta.app").is_none());
+        let _ = fs::remove_dir_all(&caskroom);
+    }
+
+    #[test]
+    fn brew_uninstall_reports_brew_failures() {
+        let runner = RecordingRunner::failing("Error: No such keg: /opt/homebrew/Cellar/wget");
+        let formula = HomebrewPackage {
+            name: "wget".to_string(),
+            cask: false,
+            path: PathBuf::from("/opt/homebrew/Cellar/wget"),
+        };
+        assert!(brew_uninstall(&runner, &formula, true, false).is_err());
+        assert_eq!(runner.commands(), vec!["brew uninstall wget"]);
+    }
+}
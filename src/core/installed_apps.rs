@@ -0,0 +1,268 @@
+// This file lists the application bundles installed on the system and reads their identity
+// (bundle identifier, bundle name, executable) from `Contents/Info.plist`.
+// It is used to decide which application data in `~/Library` still belongs to an installed app.
+
+use crate::log_debug;
+use colored::Colorize;
+use glob::glob;
+use plist::Value;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// An application bundle installed on the system.
+#[derive(Clone, Debug)]
+pub struct InstalledApp {
+    /// File name of the bundle without the `.app` extension, e.g. "Visual Studio Code".
+    pub name: String,
+    /// Absolute path of the bundle.
+    pub path: PathBuf,
+    /// `CFBundleIdentifier`, e.g. "com.microsoft.VSCode".
+    pub bundle_id: Option<String>,
+    /// `CFBundleName` and `CFBundleDisplayName`, which apps often use for their data folders.
+    pub bundle_names: Vec<String>,
+    /// `CFBundleExecutable`, the name of the main executable.
+    pub executable: Option<String>,
+    /// `CFBundleShortVersionString`, falling back to `CFBundleVersion`, e.g. "4.41.105".
+    pub version: Option<String>,
+}
+
+/// Returns the directories searched for installed apps, in the order they are searched.
+/// Apps in the system directories are included so that their data is never seen as orphaned.
+pub fn app_dirs() -> Vec<PathBuf> {
+    let home = env::var("HOME").unwrap_or_default();
+    vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from(format!("{}/Applications", home)),
+        PathBuf::from("/System/Applications"),
+        PathBuf::from("/System/Library/CoreServices"),
+    ]
+}
+
+/// Reads the identity of the app bundle at `path` from its `Info.plist`.
+/// Bundles without a readable `Info.plist` are still returned, identified by their name only.
+///
+/// Both XML and binary property lists are supported.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::installed_apps::read_app;
+/// use plist::{Dictionary, Value};
+/// use std::{env, fs};
+///
+/// let apps = env::temp_dir().join("osx-doctest-read-app");
+///
+/// // An XML Info.plist.
+/// let xml = apps.join("Visual Studio Code.app");
+/// fs::create_dir_all(xml.join("Contents")).unwrap();
+/// fs::write(
+///     xml.join("Contents/Info.plist"),
+///     r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <plist version="1.0"><dict>
+///     <key>CFBundleIdentifier</key><string>com.microsoft.VSCode</string>
+///     <key>CFBundleName</key><string>Code</string>
+///     <key>CFBundleExecutable</key><string>Electron</string>
+///     <key>CFBundleShortVersionString</key><string>1.89.1</string>
+/// </dict></plist>"#,
+/// )
+/// .unwrap();
+/// let app = read_app(&xml);
+/// assert_eq!(app.name, "Visual Studio Code");
+/// assert_eq!(app.bundle_id.as_deref(), Some("com.microsoft.VSCode"));
+/// assert_eq!(app.bundle_names, vec!["Code"]);
+/// assert_eq!(app.executable.as_deref(), Some("Electron"));
+/// assert_eq!(app.version.as_deref(), Some("1.89.1"));
+///
+/// // A binary Info.plist, as shipped by most apps.
+/// let binary = apps.join("Slack.app");
+/// fs::create_dir_all(binary.join("Contents")).unwrap();
+/// let mut info = Dictionary::new();
+/// info.insert("CFBundleIdentifier".into(), "com.tinyspeck.slackmacgap".into());
+/// info.insert("CFBundleExecutable".into(), "Slack".into());
+/// Value::Dictionary(info).to_file_binary(binary.join("Contents/Info.plist")).unwrap();
+/// let app = read_app(&binary);
+/// assert_eq!(app.bundle_id.as_deref(), Some("com.tinyspeck.slackmacgap"));
+/// assert_eq!(app.executable.as_deref(), Some("Slack"));
+///
+/// // Without an Info.plist, only the name is known.
+/// let app = read_app(&apps.join("Missing.app"));
+/// assert_eq!(app.name, "Missing");
+/// assert!(app.bundle_id.is_none());
+/// ```
+pub fn read_app(path: &Path) -> InstalledApp {
+    let info = match Value::from_file(path.join("Contents/Info.plist")) {
+        Ok(value) => value.into_dictionary(),
+        Err(e) => {
+            log_debug!("Could not read Info.plist of {}: {}", path.display(), e);
+            None
+        }
+    };
+    let string = |key: &str| {
+        info.as_ref()
+            .and_then(|i| i.get(key))
+            .and_then(Value::as_string)
+            .map(str::to_string)
+    };
+
+    InstalledApp {
+        name: path
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_path_buf(),
+        bundle_id: string("CFBundleIdentifier"),
+        bundle_names: ["CFBundleName", "CFBundleDisplayName"]
+            .iter()
+            .filter_map(|key| string(key))
+            .collect(),
+        executable: string("CFBundleExecutable"),
+        version: string("CFBundleShortVersionString").or_else(|| string("CFBundleVersion")),
+    }
+}
+
+/// Lists the apps installed in `app_dirs()`, including apps one folder deep
+/// (e.g. `/Applications/Utilities` or suites such as `/Applications/Microsoft Office`).
+pub fn installed_apps() -> Vec<InstalledApp> {
+    let mut apps = Vec::new();
+    for dir in app_dirs() {
+        let dir = glob::Pattern::escape(&dir.to_string_lossy());
+        for pattern in [format!("{}/*.app", dir), format!("{}/*/*.app", dir)] {
+            if let Ok(paths) = glob(&pattern) {
+                apps.extend(paths.flatten().map(|path| read_app(&path)));
+            }
+        }
+    }
+    apps
+}
+
+/// Result of matching a user-supplied name against the installed apps.
+#[derive(Clone, Debug)]
+pub enum AppMatch {
+    /// A single app matches the name or bundle identifier exactly.
+    Found(InstalledApp),
+    /// Several apps match exactly, or apps only match approximately: the user has to pick one.
+    Ambiguous(Vec<InstalledApp>),
+    /// No installed app matches.
+    NotFound,
+}
+
+/// Lowercases `name` and drops everything but letters and digits, so "Visual Studio Code",
+/// "visual-studio-code" and "VisualStudioCode" compare equal.
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Levenshtein distance between two strings, used to tolerate typos.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Matches `query` against the names and bundle identifiers of `apps`.
+///
+/// Matches are tried from the most to the least precise, and the first level with any match wins:
+/// 1. the bundle name, `CFBundleName` or bundle identifier, ignoring case;
+/// 2. the same, ignoring spaces and punctuation (`visual-studio-code`);
+/// 3. names containing the query, or the last component of the bundle identifier (`slackmacgap`);
+/// 4. names within two typos of the query (queries of 4 characters or more).
+///
+/// Only the first two levels can return `AppMatch::Found`: approximate matches are always returned
+/// as `AppMatch::Ambiguous` candidates, so that nothing is uninstalled on a guess.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::installed_apps::{AppMatch, InstalledApp, resolve_app};
+/// use std::path::PathBuf;
+///
+/// let app = |name: &str, id: &str| InstalledApp {
+///     name: name.to_string(),
+///     path: PathBuf::from(format!("/Applications/{}.app", name)),
+///     bundle_id: Some(id.to_string()),
+///     bundle_names: vec![],
+///     executable: None,
+///     version: None,
+/// };
+/// let apps = [
+///     app("Slack", "com.tinyspeck.slackmacgap"),
+///     app("Visual Studio Code", "com.microsoft.VSCode"),
+///     app("Microsoft Word", "com.microsoft.Word"),
+///     app("Microsoft Excel", "com.microsoft.Excel"),
+///     app("GoLand", "com.jetbrains.goland"),
+/// ];
+/// let found = |query| match resolve_app(query, &apps) {
+///     AppMatch::Found(app) => Some(app.name),
+///     _ => None,
+/// };
+///
+/// assert_eq!(found("slack").as_deref(), Some("Slack"));
+/// assert_eq!(found("com.microsoft.vscode").as_deref(), Some("Visual Studio Code"));
+/// assert_eq!(found("visual-studio-code").as_deref(), Some("Visual Studio Code"));
+///
+/// // Approximate matches are only suggested.
+/// let candidates = |query| match resolve_app(query, &apps) {
+///     AppMatch::Ambiguous(apps) => apps.into_iter().map(|app| app.name).collect(),
+///     _ => vec![],
+/// };
+/// assert_eq!(candidates("microsoft"), vec!["Microsoft Word", "Microsoft Excel"]);
+/// assert_eq!(candidates("word"), vec!["Microsoft Word"]);
+/// assert_eq!(candidates("slak"), vec!["Slack"]);
+/// assert_eq!(candidates("go"), vec!["GoLand"]);
+/// assert!(matches!(resolve_app("zoom", &apps), AppMatch::NotFound));
+/// ```
+pub fn resolve_app(query: &str, apps: &[InstalledApp]) -> AppMatch {
+    let query_lower = query.trim().to_lowercase();
+    let query_normalized = normalize(query);
+    let names = |app: &InstalledApp| -> Vec<String> {
+        let mut names = vec![app.name.clone()];
+        names.extend(app.bundle_names.iter().cloned());
+        names
+    };
+
+    let levels: [&dyn Fn(&InstalledApp) -> bool; 4] = [
+        &|app| {
+            names(app).iter().any(|n| n.to_lowercase() == query_lower)
+                || app.bundle_id.as_ref().is_some_and(|id| id.to_lowercase() == query_lower)
+        },
+        &|app| {
+            names(app).iter().chain(app.bundle_id.iter()).any(|n| normalize(n) == query_normalized)
+        },
+        &|app| {
+            !query_normalized.is_empty()
+                && (names(app).iter().any(|n| normalize(n).contains(&query_normalized))
+                    || app
+                        .bundle_id
+                        .as_ref()
+                        .and_then(|id| id.rsplit('.').next())
+                        .is_some_and(|last| normalize(last).contains(&query_normalized)))
+        },
+        &|app| {
+            query_normalized.chars().count() >= 4
+                && names(app).iter().any(|n| edit_distance(&normalize(n), &query_normalized) <= 2)
+        },
+    ];
+
+    for (level, matches) in levels.iter().enumerate() {
+        let mut candidates: Vec<InstalledApp> = apps.iter().filter(|app| matches(app)).cloned().collect();
+        // The same app may be installed in several folders: only its first location counts.
+        let mut seen = std::collections::HashSet::new();
+        candidates.retain(|app| seen.insert(app.bundle_id.clone().unwrap_or_else(|| app.name.clone())));
+        match candidates.len() {
+            0 => continue,
+            1 if level < 2 => return AppMatch::Found(candidates.remove(0)),
+            _ => return AppMatch::Ambiguous(candidates),
+        }
+    }
+    AppMatch::NotFound
+}
@@ -0,0 +1,223 @@
+// This file inspects local iOS/iPadOS device backups made by Finder (or iTunes) in
+// `~/Library/Application Support/MobileSync/Backup`.
+// Each backup folder is named after the device identifier and contains an `Info.plist`
+// (XML) and a `Manifest.plist` (usually binary) describing the device and the backup.
+// Backups are personal data, so they are only ever deleted when explicitly requested by id.
+
+use crate::core::cleaners::format_bytes;
+use crate::utils::filesystem::{calculate_dir_size, remove_path};
+use crate::{log_debug, log_error, log_info, log_warn};
+use colored::Colorize;
+use plist::{Dictionary, Value};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+use tabled::{Table, Tabled, settings::Style};
+
+/// A local backup of an iOS or iPadOS device.
+pub struct IosBackup {
+    /// Name of the backup folder, usually the device's unique identifier.
+    pub id: String,
+    /// Absolute path of the backup folder.
+    pub path: PathBuf,
+    /// Name of the device, e.g. "Jane's iPhone".
+    pub device_name: Option<String>,
+    /// Date of the last backup, in ISO 8601 format.
+    pub backup_date: Option<String>,
+    /// OS version of the device at backup time.
+    pub product_version: Option<String>,
+    /// Whether the backup is encrypted.
+    pub is_encrypted: bool,
+}
+
+/// Represents a row of the iOS backups table.
+#[derive(Tabled)]
+struct IosBackupEntry {
+    #[tabled(rename = "Id")]
+    id: String,
+    #[tabled(rename = "Device")]
+    device: String,
+    #[tabled(rename = "Version")]
+    version: String,
+    #[tabled(rename = "Last Backup")]
+    date: String,
+    #[tabled(rename = "Encrypted")]
+    encrypted: String,
+    #[tabled(rename = "Size")]
+    size: String,
+}
+
+/// Returns the directory where Finder stores local device backups.
+pub fn backups_dir() -> PathBuf {
+    let home = env::var("HOME").unwrap_or_default();
+    PathBuf::from(format!("{}/Library/Application Support/MobileSync/Backup", home))
+}
+
+/// Reads the description of the backup stored in `path`.
+///
+/// `Info.plist` provides the device name and backup date; `Manifest.plist` is used for
+/// the encryption flag and as a fallback when `Info.plist` is missing or incomplete.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::ios_backups::read_backup;
+/// use std::fs;
+///
+/// let backup = std::env::temp_dir().join(format!("osx-backup-doc-{}", std::process::id()));
+/// fs::create_dir_all(&backup).unwrap();
+/// fs::write(backup.join("Info.plist"), r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <plist version="1.0"><dict>
+///   <key>Device Name</key><string>Jane's iPhone</string>
+///   <key>Last Backup Date</key><date>2024-05-01T10:20:30Z</date>
+///   <key>Product Version</key><string>17.4.1</string>
+/// </dict></plist>"#).unwrap();
+///
+/// // Manifest.plist is a binary plist on real devices.
+/// let mut manifest = plist::Dictionary::new();
+/// manifest.insert("IsEncrypted".into(), true.into());
+/// plist::Value::Dictionary(manifest).to_file_binary(backup.join("Manifest.plist")).unwrap();
+///
+/// let info = read_backup(&backup);
+/// assert_eq!(info.device_name.as_deref(), Some("Jane's iPhone"));
+/// assert_eq!(info.backup_date.as_deref(), Some("2024-05-01T10:20:30Z"));
+/// assert_eq!(info.product_version.as_deref(), Some("17.4.1"));
+/// assert!(info.is_encrypted);
+/// fs::remove_dir_all(&backup).unwrap();
+/// ```
+pub fn read_backup(path: &Path) -> IosBackup {
+    let info = read_dictionary(&path.join("Info.plist"));
+    let manifest = read_dictionary(&path.join("Manifest.plist"));
+    let lockdown = manifest
+        .as_ref()
+        .and_then(|m| m.get("Lockdown"))
+        .and_then(Value::as_dictionary);
+
+    let info_string = |key: &str| {
+        info.as_ref()
+            .and_then(|i| i.get(key))
+            .and_then(Value::as_string)
+            .map(str::to_string)
+    };
+    let lockdown_string = |key: &str| {
+        lockdown
+            .and_then(|l| l.get(key))
+            .and_then(Value::as_string)
+            .map(str::to_string)
+    };
+    let date = |dict: Option<&Dictionary>, key: &str| {
+        dict.and_then(|d| d.get(key))
+            .and_then(Value::as_date)
+            .map(|d| d.to_xml_format())
+    };
+
+    IosBackup {
+        id: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_path_buf(),
+        device_name: info_string("Device Name")
+            .or_else(|| info_string("Display Name"))
+            .or_else(|| lockdown_string("DeviceName")),
+        backup_date: date(info.as_ref(), "Last Backup Date").or_else(|| date(manifest.as_ref(), "Date")),
+        product_version: info_string("Product Version").or_else(|| lockdown_string("ProductVersion")),
+        is_encrypted: manifest
+            .as_ref()
+            .and_then(|m| m.get("IsEncrypted"))
+            .and_then(Value::as_boolean)
+            .unwrap_or(false),
+    }
+}
+
+/// Reads a plist file (XML or binary) whose root is a dictionary.
+fn read_dictionary(path: &Path) -> Option<Dictionary> {
+    match Value::from_file(path) {
+        Ok(value) => value.into_dictionary(),
+        Err(e) => {
+            log_debug!("Could not read plist {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Lists the device backups found in `root`, sorted by backup date, newest first.
+pub fn list_backups(root: &Path) -> Vec<IosBackup> {
+    let mut backups: Vec<IosBackup> = match fs::read_dir(root) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .map(|path| read_backup(&path))
+            .collect(),
+        Err(e) => {
+            log_debug!("Could not read backup directory {}: {}", root.display(), e);
+            Vec::new()
+        }
+    };
+    backups.sort_by(|a, b| b.backup_date.cmp(&a.backup_date));
+    backups
+}
+
+/// Runs the `ios-backups` subcommand.
+///
+/// Lists every local device backup with its device name, date and size. Backups whose id
+/// is listed in `delete` are removed through `remove_path` (simulated in `dry_run`).
+pub fn run_ios_backups(delete: &[String], dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let root = backups_dir();
+    let backups = list_backups(&root);
+    if backups.is_empty() {
+        log_info!("No iOS device backups found in {}", root.display().to_string().white().dimmed());
+    } else {
+        let mut total_size = 0;
+        let mut entries = Vec::new();
+        for backup in &backups {
+            let size = calculate_dir_size(&backup.path).unwrap_or_else(|e| {
+                log_warn!("Could not determine size of backup {}: {}", backup.path.display(), e);
+                0
+            });
+            total_size += size;
+            entries.push(IosBackupEntry {
+                id: backup.id.clone(),
+                device: backup.device_name.clone().unwrap_or_else(|| "Unknown".to_string()),
+                version: backup.product_version.clone().unwrap_or_default(),
+                date: backup.backup_date.clone().unwrap_or_else(|| "Unknown".to_string()),
+                encrypted: if backup.is_encrypted { "Yes" } else { "No" }.to_string(),
+                size: format_bytes(size),
+            });
+        }
+        entries.push(IosBackupEntry {
+            id: "Total".to_string(),
+            device: String::new(),
+            version: String::new(),
+            date: String::new(),
+            encrypted: String::new(),
+            size: format_bytes(total_size),
+        });
+
+        println!("\n{}\n", "📱 iOS Device Backups".bold().underline().purple());
+        println!("{}", Table::new(&entries).with(Style::modern()));
+    }
+
+    let mut failures = 0;
+    for id in delete {
+        let Some(backup) = backups.iter().find(|b| &b.id == id) else {
+            log_error!("No backup with id '{}' found.", id);
+            failures += 1;
+            continue;
+        };
+        match remove_path(&backup.path, dry_run) {
+            Ok(_) if dry_run => log_info!("{}: {}", "Would delete".bright_green(), backup.path.display()),
+            Ok(_) => log_info!("{}: {}", "Deleted".bright_green(), backup.path.display()),
+            Err(e) => {
+                log_error!("❌ Failed to delete backup {}: {}", backup.path.display(), e);
+                failures += 1;
+            }
+        }
+    }
+    if failures > 0 {
+        return Err(format!("{} backup(s) could not be deleted", failures).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+// This file handles the launchd jobs of an app being uninstalled. Deleting a LaunchAgent or
+// LaunchDaemon plist doesn't stop its job: it stays loaded until the next reboot. Jobs are therefore
+// booted out with `launchctl` first, and the privileged helper binaries they run are removed too.
+
+use crate::utils::command::CommandRunner;
+use crate::log_debug;
+use colored::Colorize;
+use plist::Value;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Folder where apps install the helper tools they run as root through a LaunchDaemon.
+pub const PRIVILEGED_HELPER_TOOLS_DIR: &str = "/Library/PrivilegedHelperTools";
+
+/// A launchd job, as described by its plist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LaunchJob {
+    /// Path of the job's plist.
+    pub plist: PathBuf,
+    /// `Label`, which identifies the job in its launchd domain.
+    pub label: Option<String>,
+    /// `Program`, or the first element of `ProgramArguments`.
+    pub program: Option<PathBuf>,
+}
+
+impl LaunchJob {
+    /// Reads the launchd job described by the plist at `path` (XML or binary).
+    /// Unreadable plists give a job without label or program.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use osx::core::launchd::LaunchJob;
+    /// use std::{env, fs, path::PathBuf};
+    ///
+    /// let plist = env::temp_dir().join("osx-doctest-launchd/com.example.helper.plist");
+    /// fs::create_dir_all(plist.parent().unwrap()).unwrap();
+    /// fs::write(
+    ///     &plist,
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <plist version="1.0"><dict>
+    ///     <key>Label</key><string>com.example.helper</string>
+    ///     <key>ProgramArguments</key>
+    ///     <array><string>/Library/PrivilegedHelperTools/com.example.helper</string><string>--daemon</string></array>
+    /// </dict></plist>"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// let job = LaunchJob::read(&plist);
+    /// assert_eq!(job.label.as_deref(), Some("com.example.helper"));
+    /// assert_eq!(job.program, Some(PathBuf::from("/Library/PrivilegedHelperTools/com.example.helper")));
+    /// assert_eq!(job.privileged_helpers(), vec![PathBuf::from("/Library/PrivilegedHelperTools/com.example.helper")]);
+    /// ```
+    pub fn read(path: &Path) -> Self {
+        let job = match Value::from_file(path) {
+            Ok(value) => value.into_dictionary(),
+            Err(e) => {
+                log_debug!("Could not read launchd plist {}: {}", path.display(), e);
+                None
+            }
+        };
+        let label = job
+            .as_ref()
+            .and_then(|j| j.get("Label"))
+            .and_then(Value::as_string)
+            .map(str::to_string);
+        let program = job.as_ref().and_then(|j| {
+            j.get("Program").and_then(Value::as_string).or_else(|| {
+                j.get("ProgramArguments")
+                    .and_then(Value::as_array)
+                    .and_then(|args| args.first())
+                    .and_then(Value::as_string)
+            })
+        });
+
+        LaunchJob {
+            plist: path.to_path_buf(),
+            label,
+            program: program.map(PathBuf::from),
+        }
+    }
+
+    /// Returns the helper binaries of the job in `/Library/PrivilegedHelperTools`: the program it runs,
+    /// and the helper named after its label (the convention of `SMJobBless`). They may not exist.
+    pub fn privileged_helpers(&self) -> Vec<PathBuf> {
+        let helpers_dir = Path::new(PRIVILEGED_HELPER_TOOLS_DIR);
+        let mut helpers: Vec<PathBuf> = self
+            .program
+            .iter()
+            .filter(|program| program.starts_with(helpers_dir))
+            .cloned()
+            .collect();
+        if let Some(label) = &self.label {
+            let named = helpers_dir.join(label);
+            if !helpers.contains(&named) {
+                helpers.push(named);
+            }
+        }
+        helpers
+    }
+
+    /// Returns the launchd domain the job is loaded in: `system` for LaunchDaemons,
+    /// and the GUI session of the user `uid` (`gui/<uid>`) for LaunchAgents.
+    pub fn domain(&self, uid: u32) -> String {
+        if self.plist.starts_with("/Library/LaunchDaemons") {
+            return "system".to_string();
+        }
+        format!("gui/{}", uid)
+    }
+}
+
+/// Unloads `job` with `launchctl bootout <domain>/<label>`; LaunchAgents are looked up in the GUI session
+/// of the user `uid` (see `LaunchJob::domain`).
+///
+/// A job that isn't loaded is not an error. Jobs without a label are left alone.
+pub fn bootout(runner: &dyn CommandRunner, job: &LaunchJob, uid: u32) -> io::Result<()> {
+    let Some(label) = &job.label else {
+        log_debug!("No label in {}, nothing to unload", job.plist.display());
+        return Ok(());
+    };
+    let target = format!("{}/{}", job.domain(uid), label);
+    let output = runner.run("launchctl", &["bootout", &target])?;
+    // Status 3 ("No such process") and 113 ("Could not find specified service"): the job isn't loaded.
+    let not_loaded = ["No such process", "Could not find"]
+        .iter()
+        .any(|message| output.stderr.contains(message) || output.stdout.contains(message));
+    if output.success || not_loaded {
+        log_debug!("Unloaded launchd job {}", target.bright_white());
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "`launchctl bootout {}` failed: {}",
+            target,
+            output.stderr.trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::command::testing::RecordingRunner;
+
+    fn vmnetd() -> LaunchJob {
+        LaunchJob {
+            plist: PathBuf::from("/Library/LaunchDaemons/com.docker.vmnetd.plist"),
+            label: Some("com.docker.vmnetd".to_string()),
+            program: None,
+        }
+    }
+
+    #[test]
+    fn bootout_accepts_jobs_that_are_not_loaded() {
+        // What `launchctl` answers for a job that isn't loaded.
+        let runner = RecordingRunner::failing("Boot-out failed: 3: No such process");
+        assert!(bootout(&runner, &vmnetd(), 501).is_ok());
+        assert_eq!(runner.commands(), vec!["launchctl bootout system/com.docker.vmnetd"]);
+    }
+
+    #[test]
+    fn bootout_reports_other_failures() {
+        let runner = RecordingRunner::failing("Boot-out failed: 1: Operation not permitted");
+        assert!(bootout(&runner, &vmnetd(), 501).is_err());
+    }
+}
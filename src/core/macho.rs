@@ -0,0 +1,497 @@
+// This file implements universal ("fat") Mach-O binary inspection and thinning for the
+// `thin-binaries` subcommand and the report-only `UniversalBinariesCleaner`.
+// A universal binary starts with a big-endian fat header listing one slice per architecture;
+// every slice is a complete Mach-O file, so thinning simply keeps the host slice.
+// Parsing is done in pure Rust (no `lipo`), so it also works on fixture files on Linux.
+
+use crate::core::cleaners::format_bytes;
+use crate::utils::command::CommandRunner;
+use crate::{log_debug, log_error, log_info, log_warn};
+use colored::Colorize;
+use glob::glob;
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use tabled::{Table, Tabled, settings::Style};
+use walkdir::WalkDir;
+
+/// Magic number of a fat header with 32-bit offsets (`FAT_MAGIC`).
+const FAT_MAGIC: u32 = 0xcafe_babe;
+/// Magic number of a fat header with 64-bit offsets (`FAT_MAGIC_64`).
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+/// Java class files share `FAT_MAGIC`; their next field (the class version) is always at least 45,
+/// while real universal binaries have far fewer slices.
+const MAX_FAT_ARCHS: u32 = 45;
+
+/// Flag marking the 64-bit variant of a CPU family.
+const CPU_ARCH_ABI64: u32 = 0x0100_0000;
+/// Flag marking the 64-bit variant with 32-bit pointers (watchOS).
+const CPU_ARCH_ABI64_32: u32 = 0x0200_0000;
+const CPU_TYPE_X86: u32 = 7;
+const CPU_TYPE_ARM: u32 = 12;
+const CPU_TYPE_POWERPC: u32 = 18;
+pub const CPU_TYPE_X86_64: u32 = CPU_TYPE_X86 | CPU_ARCH_ABI64;
+pub const CPU_TYPE_ARM64: u32 = CPU_TYPE_ARM | CPU_ARCH_ABI64;
+/// The high byte of a CPU subtype holds capability flags, not the subtype itself.
+const CPU_SUBTYPE_MASK: u32 = 0xff00_0000;
+const CPU_SUBTYPE_ARM64E: u32 = 2;
+
+/// One architecture slice of a universal binary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FatArch {
+    pub cpu_type: u32,
+    pub cpu_subtype: u32,
+    /// Offset of the slice from the start of the file.
+    pub offset: u64,
+    /// Size of the slice in bytes.
+    pub size: u64,
+    /// Alignment of the slice, as a power of two.
+    pub align: u32,
+}
+
+impl FatArch {
+    /// Returns the usual name of the slice's architecture (as printed by `lipo -archs`).
+    pub fn name(&self) -> String {
+        match (self.cpu_type, self.cpu_subtype & !CPU_SUBTYPE_MASK) {
+            (CPU_TYPE_ARM64, CPU_SUBTYPE_ARM64E) => "arm64e".to_string(),
+            (CPU_TYPE_ARM64, _) => "arm64".to_string(),
+            (CPU_TYPE_X86_64, _) => "x86_64".to_string(),
+            (CPU_TYPE_X86, _) => "i386".to_string(),
+            (CPU_TYPE_ARM, _) => "arm".to_string(),
+            (t, _) if t == CPU_TYPE_ARM | CPU_ARCH_ABI64_32 => "arm64_32".to_string(),
+            (t, _) if t == CPU_TYPE_POWERPC | CPU_ARCH_ABI64 => "ppc64".to_string(),
+            (CPU_TYPE_POWERPC, _) => "ppc".to_string(),
+            (t, s) => format!("cputype {} subtype {}", t, s),
+        }
+    }
+}
+
+/// Parses the fat header at the start of `data`.
+///
+/// Returns `None` if `data` is not a universal binary (e.g. a thin Mach-O, a Java class file or
+/// a script) or if the header is truncated.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::macho::{CPU_TYPE_ARM64, CPU_TYPE_X86_64, parse_fat_header};
+///
+/// // A fat header with two slices: x86_64 at 0x1000 and arm64 at 0x4000.
+/// let mut header = Vec::new();
+/// for field in [0xcafebabe_u32, 2, CPU_TYPE_X86_64, 3, 0x1000, 0x2000, 12, CPU_TYPE_ARM64, 0, 0x4000, 0x3000, 14] {
+///     header.extend_from_slice(&field.to_be_bytes());
+/// }
+///
+/// let archs = parse_fat_header(&header).unwrap();
+/// assert_eq!(archs.len(), 2);
+/// assert_eq!(archs[0].name(), "x86_64");
+/// assert_eq!((archs[1].name().as_str(), archs[1].offset, archs[1].size), ("arm64", 0x4000, 0x3000));
+///
+/// // Thin Mach-O files (here a 64-bit little-endian header) and truncated headers are not fat.
+/// assert_eq!(parse_fat_header(&0xfeedfacf_u32.to_le_bytes()), None);
+/// assert_eq!(parse_fat_header(&header[..30]), None);
+/// ```
+pub fn parse_fat_header(data: &[u8]) -> Option<Vec<FatArch>> {
+    let read_u32 = |at: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+    };
+    let read_u64 = |at: usize| -> Option<u64> {
+        Some(u64::from_be_bytes(data.get(at..at + 8)?.try_into().ok()?))
+    };
+
+    let is_64 = match read_u32(0)? {
+        FAT_MAGIC => false,
+        FAT_MAGIC_64 => true,
+        _ => return None,
+    };
+    let count = read_u32(4)?;
+    if count == 0 || count >= MAX_FAT_ARCHS {
+        return None;
+    }
+
+    // `fat_arch` is 20 bytes; `fat_arch_64` is 32 bytes (64-bit offset and size, plus a reserved field).
+    let entry_size = if is_64 { 32 } else { 20 };
+    (0..count as usize)
+        .map(|index| {
+            let at = 8 + index * entry_size;
+            let (offset, size, align) = if is_64 {
+                (read_u64(at + 8)?, read_u64(at + 16)?, read_u32(at + 24)?)
+            } else {
+                (read_u32(at + 8)? as u64, read_u32(at + 12)? as u64, read_u32(at + 16)?)
+            };
+            Some(FatArch {
+                cpu_type: read_u32(at)?,
+                cpu_subtype: read_u32(at + 4)?,
+                offset,
+                size,
+                align,
+            })
+        })
+        .collect()
+}
+
+/// Reads the fat header of the file at `path`.
+///
+/// Returns `Ok(None)` if the file is not a universal binary, or if one of its slices
+/// lies outside of the file.
+pub fn read_fat_archs(path: &Path) -> io::Result<Option<Vec<FatArch>>> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    // The largest valid header (44 slices of `fat_arch_64`) fits in 8 + 44 * 32 bytes.
+    let mut header = Vec::with_capacity(1416);
+    file.by_ref().take(1416).read_to_end(&mut header)?;
+    Ok(parse_fat_header(&header)
+        .filter(|archs| archs.iter().all(|a| a.offset.saturating_add(a.size) <= file_len)))
+}
+
+/// Returns the CPU type of the host.
+///
+/// A binary built for Intel running under Rosetta reports the Apple Silicon CPU type,
+/// so x86_64 slices are never kept on an Apple Silicon Mac because of the tool's own build.
+pub fn host_cpu_type() -> Option<u32> {
+    match std::env::consts::ARCH {
+        "aarch64" => Some(CPU_TYPE_ARM64),
+        "x86_64" if is_translated() => Some(CPU_TYPE_ARM64),
+        "x86_64" => Some(CPU_TYPE_X86_64),
+        _ => None,
+    }
+}
+
+/// Checks if the current process runs under Rosetta translation, using `sysctl`.
+fn is_translated() -> bool {
+    Command::new("sysctl")
+        .args(["-n", "sysctl.proc_translated"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
+
+/// Returns the number of bytes freed by keeping only the `host` slice of `archs`.
+///
+/// Returns `None` unless exactly one slice matches the host and at least one does not,
+/// since a binary can only be thinned to a single slice.
+pub fn reclaimable_bytes(archs: &[FatArch], host: u32) -> Option<u64> {
+    let matching = archs.iter().filter(|a| a.cpu_type == host).count();
+    if matching != 1 || archs.len() < 2 {
+        return None;
+    }
+    Some(archs.iter().filter(|a| a.cpu_type != host).map(|a| a.size).sum())
+}
+
+/// Returns the universal binaries inside `app` that can be thinned for `host`,
+/// along with their slices.
+///
+/// Only executable files are inspected, which covers main executables, frameworks, dylibs
+/// and helper tools without reading every resource of the bundle.
+pub fn find_thinnable_binaries(app: &Path, host: u32) -> Vec<(PathBuf, Vec<FatArch>)> {
+    WalkDir::new(app)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_type().is_file()
+                && e.metadata().is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+        })
+        .filter_map(|e| match read_fat_archs(e.path()) {
+            Ok(Some(archs)) if reclaimable_bytes(&archs, host).is_some() => {
+                Some((e.into_path(), archs))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                log_debug!("Could not read Mach-O header: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Replaces the universal binary at `path` with its slice for `cpu_type` and returns the number
+/// of bytes freed.
+///
+/// The slice is written next to the binary and then renamed over it, keeping the original
+/// permissions. The code signature of the enclosing app is not checked here: `thin_app` verifies it.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::macho::{CPU_TYPE_ARM64, CPU_TYPE_X86_64, read_fat_archs, thin_binary};
+/// use std::fs;
+///
+/// // A fixture universal binary: fat header, then a 16-byte x86_64 slice and an 8-byte arm64 slice.
+/// let mut binary = Vec::new();
+/// for field in [0xcafebabe_u32, 2, CPU_TYPE_X86_64, 3, 48, 16, 0, CPU_TYPE_ARM64, 0, 64, 8, 0] {
+///     binary.extend_from_slice(&field.to_be_bytes());
+/// }
+/// binary.extend_from_slice(b"x86_64 slice....");
+/// binary.extend_from_slice(b"arm64...");
+///
+/// let path = std::env::temp_dir().join(format!("osx-macho-doc-{}", std::process::id()));
+/// fs::write(&path, &binary).unwrap();
+/// assert_eq!(read_fat_archs(&path).unwrap().unwrap().len(), 2);
+///
+/// assert_eq!(thin_binary(&path, CPU_TYPE_ARM64).unwrap(), 64);
+/// assert_eq!(fs::read(&path).unwrap(), b"arm64...");
+/// assert_eq!(read_fat_archs(&path).unwrap(), None);
+/// fs::remove_file(&path).unwrap();
+/// ```
+pub fn thin_binary(path: &Path, cpu_type: u32) -> io::Result<u64> {
+    let archs = read_fat_archs(path)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a universal binary"))?;
+    let slice = archs
+        .iter()
+        .find(|a| a.cpu_type == cpu_type)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no slice for the host architecture"))?;
+
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let thin_path = path.with_file_name(format!(".{}.osx-thin", file_name));
+
+    let result = (|| {
+        file.seek(SeekFrom::Start(slice.offset))?;
+        let mut thin = File::create(&thin_path)?;
+        io::copy(&mut file.by_ref().take(slice.size), &mut thin)?;
+        thin.set_permissions(metadata.permissions())?;
+        fs::rename(&thin_path, path)
+    })();
+    if result.is_err() {
+        // Never leave a partial copy behind.
+        let _ = fs::remove_file(&thin_path);
+    }
+    result?;
+    Ok(metadata.len().saturating_sub(slice.size))
+}
+
+/// Returns `true` if `codesign --verify --deep --strict` accepts the signature of the bundle `app`.
+/// A missing `codesign` counts as an invalid signature.
+fn signature_is_valid(runner: &dyn CommandRunner, app: &Path) -> bool {
+    let app = app.to_string_lossy();
+    match runner.run("codesign", &["--verify", "--deep", "--strict", &app]) {
+        Ok(output) => output.success,
+        Err(e) => {
+            log_debug!("Could not run codesign on {}: {}", app, e);
+            false
+        }
+    }
+}
+
+/// Thins the universal `binaries` of the app bundle `app` to their `cpu_type` slice (see `thin_binary`),
+/// and returns the bytes freed for each binary.
+///
+/// Thinning doesn't keep every signature valid, so the app's signature is checked with `codesign` through
+/// `runner` before and after. If the app was validly signed and no longer is, every binary is restored
+/// from a backup and an error is returned. Apps whose signature can't be verified beforehand are thinned
+/// with a warning: they may need to be re-signed.
+pub fn thin_app(
+    runner: &dyn CommandRunner,
+    app: &Path,
+    binaries: &[PathBuf],
+    cpu_type: u32,
+) -> io::Result<Vec<io::Result<u64>>> {
+    let signed = signature_is_valid(runner, app);
+    let backup_of = |path: &Path| {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        path.with_file_name(format!(".{}.osx-fat", file_name))
+    };
+
+    let mut results = Vec::new();
+    let mut thinned = Vec::new();
+    for path in binaries {
+        let backup = backup_of(path);
+        let result = fs::copy(path, &backup).and_then(|_| thin_binary(path, cpu_type));
+        match result {
+            Ok(_) => thinned.push(path),
+            Err(_) => {
+                let _ = fs::remove_file(&backup);
+            }
+        }
+        results.push(result);
+    }
+    if thinned.is_empty() {
+        return Ok(results);
+    }
+
+    if signed && !signature_is_valid(runner, app) {
+        for path in &thinned {
+            fs::rename(backup_of(path), path)?;
+        }
+        return Err(io::Error::other(format!(
+            "the code signature of {} is no longer valid after thinning; its binaries were restored",
+            app.display()
+        )));
+    }
+    for path in &thinned {
+        let _ = fs::remove_file(backup_of(path));
+    }
+    if !signed {
+        log_warn!(
+            "The signature of {} could not be verified: it was thinned anyway and may need to be re-signed",
+            app.display()
+        );
+    }
+    Ok(results)
+}
+
+/// Represents a row of the universal binaries table.
+#[derive(Tabled)]
+struct UniversalBinaryEntry {
+    #[tabled(rename = "Binary")]
+    path: String,
+    #[tabled(rename = "Architectures")]
+    archs: String,
+    #[tabled(rename = "Reclaimable")]
+    size: String,
+    #[tabled(rename = "Action")]
+    action: String,
+}
+
+/// Runs the `thin-binaries` subcommand.
+///
+/// Lists the universal binaries of the given app bundles (all of `/Applications` by default)
+/// with the bytes used by slices for other architectures. With `strip`, those slices are removed
+/// through `thin_app` (simulated in `dry_run`), which checks the apps' signatures with `codesign` through `runner`.
+pub fn run_thin_binaries(
+    runner: &dyn CommandRunner,
+    apps: &[PathBuf],
+    strip: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let host = host_cpu_type().ok_or("unsupported host architecture")?;
+    let apps: Vec<PathBuf> = if apps.is_empty() {
+        glob("/Applications/*.app")?.flatten().collect()
+    } else {
+        apps.to_vec()
+    };
+
+    let mut entries = Vec::new();
+    let mut total_reclaimable = 0;
+    let mut failures = 0;
+    for app in &apps {
+        log_info!("🔍 Inspecting: {}", app.display().to_string().blue());
+        let binaries = find_thinnable_binaries(app, host);
+        let paths: Vec<PathBuf> = binaries.iter().map(|(path, _)| path.clone()).collect();
+        let actions: Vec<String> = if !strip {
+            vec![String::new(); paths.len()]
+        } else if dry_run {
+            vec!["Would strip".to_string(); paths.len()]
+        } else {
+            match thin_app(runner, app, &paths, host) {
+                Ok(results) => results
+                    .into_iter()
+                    .zip(&paths)
+                    .map(|(result, path)| match result {
+                        Ok(_) => "Stripped".to_string(),
+                        Err(e) => {
+                            log_error!("❌ Failed to strip {}: {}", path.display(), e);
+                            failures += 1;
+                            format!("Failed: {}", e)
+                        }
+                    })
+                    .collect(),
+                Err(e) => {
+                    log_error!("❌ {}", e);
+                    failures += paths.len();
+                    vec!["Restored: signature broken".to_string(); paths.len()]
+                }
+            }
+        };
+        for ((path, archs), action) in binaries.into_iter().zip(actions) {
+            let reclaimable = reclaimable_bytes(&archs, host).unwrap_or(0);
+            total_reclaimable += reclaimable;
+            entries.push(UniversalBinaryEntry {
+                path: path.display().to_string(),
+                archs: archs.iter().map(FatArch::name).collect::<Vec<_>>().join(", "),
+                size: format_bytes(reclaimable),
+                action,
+            });
+        }
+    }
+
+    if entries.is_empty() {
+        log_info!("{}", "No universal binaries to thin.".bright_white());
+        return Ok(());
+    }
+    entries.push(UniversalBinaryEntry {
+        path: "Total Reclaimable".to_string(),
+        archs: String::new(),
+        size: format_bytes(total_reclaimable),
+        action: String::new(),
+    });
+
+    println!("\n{}\n", "🧬 Universal Binaries".bold().underline().purple());
+    println!("{}", Table::new(&entries).with(Style::modern()));
+    if !strip {
+        eprintln!();
+        log_info!(
+            "🧠 Reclaimable by keeping only the host architecture: {} (use --strip to thin the binaries)",
+            format_bytes(total_reclaimable).bright_green().bold()
+        );
+    }
+    if failures > 0 {
+        return Err(format!("{} binary(ies) could not be thinned", failures).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::command::CommandOutput;
+    use crate::utils::command::testing::RecordingRunner;
+    use std::{env, process};
+
+    /// Writes a universal binary with 16-byte x86_64 and arm64 slices, and returns its contents.
+    fn write_fat_binary(path: &Path) -> Vec<u8> {
+        let mut data = Vec::new();
+        for field in [FAT_MAGIC, 2, CPU_TYPE_X86_64, 3, 0x1000, 0x10, 12, CPU_TYPE_ARM64, 0, 0x2000, 0x10, 14] {
+            data.extend_from_slice(&field.to_be_bytes());
+        }
+        data.resize(0x1000, 0);
+        data.extend_from_slice(&[0x86; 0x10]);
+        data.resize(0x2000, 0);
+        data.extend_from_slice(&[0xa6; 0x10]);
+        fs::write(path, &data).unwrap();
+        data
+    }
+
+    fn verification(success: bool) -> CommandOutput {
+        CommandOutput { success, stdout: String::new(), stderr: String::new() }
+    }
+
+    fn fixture_app(name: &str) -> (PathBuf, PathBuf) {
+        let app = env::temp_dir().join(format!("osx-test-{}-{}", name, process::id())).join("Fat.app");
+        let _ = fs::remove_dir_all(&app);
+        let binary = app.join("Contents/MacOS/Fat");
+        fs::create_dir_all(binary.parent().unwrap()).unwrap();
+        (app, binary)
+    }
+
+    #[test]
+    fn binaries_are_restored_when_thinning_breaks_the_signature() {
+        let (app, binary) = fixture_app("thin-restore");
+        let original = write_fat_binary(&binary);
+        let runner = RecordingRunner::answering_in_turn(vec![verification(true), verification(false)]);
+
+        assert!(thin_app(&runner, &app, std::slice::from_ref(&binary), CPU_TYPE_ARM64).is_err());
+        assert_eq!(fs::read(&binary).unwrap(), original);
+        assert_eq!(fs::read_dir(binary.parent().unwrap()).unwrap().count(), 1);
+        assert_eq!(runner.commands().len(), 2);
+        fs::remove_dir_all(app.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn binaries_stay_thinned_when_the_signature_holds() {
+        let (app, binary) = fixture_app("thin-keep");
+        write_fat_binary(&binary);
+        let runner = RecordingRunner::succeeding();
+
+        let results = thin_app(&runner, &app, std::slice::from_ref(&binary), CPU_TYPE_ARM64).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_ok());
+        assert_eq!(fs::read(&binary).unwrap(), vec![0xa6; 0x10]);
+        assert_eq!(fs::read_dir(binary.parent().unwrap()).unwrap().count(), 1);
+        fs::remove_dir_all(app.parent().unwrap()).unwrap();
+    }
+}
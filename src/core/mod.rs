@@ -2,3 +2,16 @@ pub mod uninstaller;
 pub mod version;
 pub mod cleaners;
 pub mod cleaner_orchestrator;
+pub mod duplicates;
+pub mod ios_backups;
+pub mod macho;
+pub mod installed_apps;
+pub mod time_machine;
+pub mod toolchains;
+pub mod apps;
+pub mod uninstall_plan;
+pub mod launchd;
+pub mod receipts;
+pub mod homebrew;
+pub mod package_managers;
+pub mod processes;
@@ -0,0 +1,323 @@
+// This file recognizes CLI tools installed by language package managers (`cargo install`, `npm -g`,
+// `pipx`, `go install`, `gem`) so that uninstalling them also updates the manager's own metadata.
+// The manager owning a binary is found from where the binary lives, or where its symlink points.
+
+use crate::utils::command::CommandRunner;
+use crate::utils::filesystem::remove_path;
+use crate::{log_debug, log_info};
+use colored::Colorize;
+use serde_json::Value;
+use std::{
+    env, fmt, fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// A language package manager that installs CLI tools.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PackageManager {
+    Cargo,
+    Npm,
+    Pipx,
+    Go,
+    Gem,
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PackageManager::Cargo => "cargo",
+            PackageManager::Npm => "npm",
+            PackageManager::Pipx => "pipx",
+            PackageManager::Go => "go",
+            PackageManager::Gem => "gem",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A CLI tool installed by a language package manager.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManagedTool {
+    pub manager: PackageManager,
+    /// Package as named by the manager: the `.crates2.json` key for cargo (e.g.
+    /// `ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)`), the package, venv or gem name
+    /// for the others, and the binary name for go.
+    pub package: String,
+    /// Paths the manager removes: binaries, package folder or virtualenv.
+    pub paths: Vec<PathBuf>,
+}
+
+/// Returns the cargo home: `CARGO_HOME`, or `~/.cargo`.
+pub fn cargo_home() -> PathBuf {
+    env::var("CARGO_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(format!("{}/.cargo", env::var("HOME").unwrap_or_default())))
+}
+
+/// Returns the folders `go install` puts binaries in: `GOBIN`, `<GOPATH>/bin` and `~/go/bin`.
+fn go_bin_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::var("GOBIN").map(PathBuf::from).into_iter().collect();
+    if let Some(gopath) = env::var_os("GOPATH") {
+        dirs.extend(env::split_paths(&gopath).map(|path| path.join("bin")));
+    }
+    dirs.push(PathBuf::from(format!("{}/go/bin", env::var("HOME").unwrap_or_default())));
+    dirs
+}
+
+/// Finds the executable `name` in the folders of `PATH`.
+pub fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.symlink_metadata().is_ok())
+}
+
+/// Finds the crate installed with `cargo install` that provides the binary `bin_name`, from the
+/// metadata in `cargo_home` (`.crates2.json`, or `.crates.toml` for older cargo versions).
+/// Returns the crate's key and all its binaries.
+pub fn find_cargo_package(cargo_home: &Path, bin_name: &str) -> Option<(String, Vec<String>)> {
+    if let Ok(content) = fs::read_to_string(cargo_home.join(".crates2.json"))
+        && let Ok(metadata) = serde_json::from_str::<Value>(&content)
+        && let Some(installs) = metadata.get("installs").and_then(Value::as_object)
+    {
+        for (key, install) in installs {
+            let bins: Vec<String> = install
+                .get("bins")
+                .and_then(Value::as_array)
+                .map(|bins| bins.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                .unwrap_or_default();
+            if bins.iter().any(|bin| bin == bin_name) {
+                return Some((key.clone(), bins));
+            }
+        }
+    }
+
+    // `.crates.toml` lines look like `"ripgrep 14.1.0 (registry+...)" = ["rg"]`.
+    let content = fs::read_to_string(cargo_home.join(".crates.toml")).ok()?;
+    content.lines().find_map(|line| {
+        let (key, bins) = line.split_once("\" = [")?;
+        let bins: Vec<String> = bins
+            .trim_end_matches(']')
+            .split(',')
+            .map(|bin| bin.trim().trim_matches('"').to_string())
+            .filter(|bin| !bin.is_empty())
+            .collect();
+        bins.iter()
+            .any(|bin| bin == bin_name)
+            .then(|| (key.trim_start_matches('"').to_string(), bins))
+    })
+}
+
+/// Removes the binaries of the crate `key` from `<cargo_home>/bin` and forgets it in `.crates2.json`
+/// and `.crates.toml`, as `cargo uninstall` does. Nothing is changed in `dry_run`.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::package_managers::{find_cargo_package, remove_cargo_package};
+/// use std::{env, fs};
+///
+/// let key = "ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)";
+/// let cargo_home = env::temp_dir().join(format!("osx-doctest-cargo-home-{}", std::process::id()));
+/// fs::create_dir_all(cargo_home.join("bin")).unwrap();
+/// fs::write(cargo_home.join("bin/rg"), "").unwrap();
+/// fs::write(cargo_home.join("bin/bat"), "").unwrap();
+/// fs::write(
+///     cargo_home.join(".crates2.json"),
+///     format!(r#"{{"installs":{{"{}":{{"bins":["rg"]}},"bat 0.24.0 (registry+https://github.com/rust-lang/crates.io-index)":{{"bins":["bat"]}}}}}}"#, key),
+/// )
+/// .unwrap();
+/// fs::write(
+///     cargo_home.join(".crates.toml"),
+///     format!("[v1]\n\"{}\" = [\"rg\"]\n\"bat 0.24.0 (registry+https://github.com/rust-lang/crates.io-index)\" = [\"bat\"]\n", key),
+/// )
+/// .unwrap();
+///
+/// let (found, bins) = find_cargo_package(&cargo_home, "rg").unwrap();
+/// assert_eq!(found, key);
+/// assert_eq!(bins, vec!["rg"]);
+///
+/// remove_cargo_package(&cargo_home, &found, &bins, false).unwrap();
+/// assert!(!cargo_home.join("bin/rg").exists());
+/// assert!(cargo_home.join("bin/bat").exists());
+/// assert!(find_cargo_package(&cargo_home, "rg").is_none());
+/// assert!(find_cargo_package(&cargo_home, "bat").is_some());
+/// assert!(!fs::read_to_string(cargo_home.join(".crates.toml")).unwrap().contains("ripgrep"));
+/// ```
+pub fn remove_cargo_package(cargo_home: &Path, key: &str, bins: &[String], dry_run: bool) -> io::Result<()> {
+    for bin in bins {
+        remove_path(&cargo_home.join("bin").join(bin), dry_run)?;
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let json_path = cargo_home.join(".crates2.json");
+    if let Ok(content) = fs::read_to_string(&json_path) {
+        let mut metadata: Value = serde_json::from_str(&content).map_err(io::Error::other)?;
+        if let Some(installs) = metadata.get_mut("installs").and_then(Value::as_object_mut) {
+            installs.remove(key);
+        }
+        fs::write(&json_path, serde_json::to_string(&metadata).map_err(io::Error::other)?)?;
+    }
+    let toml_path = cargo_home.join(".crates.toml");
+    if let Ok(content) = fs::read_to_string(&toml_path) {
+        let entry = format!("\"{}\" =", key);
+        let kept: Vec<&str> = content.lines().filter(|line| !line.starts_with(&entry)).collect();
+        fs::write(&toml_path, kept.join("\n") + "\n")?;
+    }
+    Ok(())
+}
+
+/// Returns the folder after the `marker` component of `path`, and its path: the package of an
+/// npm (`node_modules`, including `@scope/name` packages) or pipx (`venvs`) install.
+fn package_after(path: &Path, marker: &str) -> Option<(String, PathBuf)> {
+    let components: Vec<Component> = path.components().collect();
+    let position = components.iter().rposition(|c| c.as_os_str() == marker)?;
+    let mut end = position + 2;
+    let first = components.get(position + 1)?.as_os_str().to_string_lossy().to_string();
+    let name = if first.starts_with('@') {
+        end += 1;
+        format!("{}/{}", first, components.get(position + 2)?.as_os_str().to_string_lossy())
+    } else {
+        first
+    };
+    Some((name, components[..end].iter().collect()))
+}
+
+/// Finds the language package manager owning the executable at `binary`, with the cargo home
+/// and `go install` folders of the environment (see `detect_managed_tool_in`).
+pub fn detect_managed_tool(binary: &Path) -> Option<ManagedTool> {
+    detect_managed_tool_in(binary, &cargo_home(), &go_bin_dirs())
+}
+
+/// Finds the language package manager owning the executable at `binary`, given the cargo home
+/// `cargo_home` and the folders `go install` puts binaries in (`go_bin_dirs`).
+///
+/// # Example
+///
+/// ```
+/// use osx::core::package_managers::{PackageManager, detect_managed_tool_in};
+/// use std::{env, fs, os::unix::fs::symlink, path::PathBuf};
+///
+/// let home = fs::canonicalize(env::temp_dir())
+///     .unwrap()
+///     .join(format!("osx-doctest-managed-tools-{}", std::process::id()));
+/// let _ = fs::remove_dir_all(&home);
+/// let cargo_home = home.join(".cargo");
+/// let go_bin_dirs = [home.join("go/bin")];
+/// let detect_managed_tool = |binary: &PathBuf| detect_managed_tool_in(binary, &cargo_home, &go_bin_dirs);
+///
+/// // `npm -g` links binaries to the package's script in `lib/node_modules`.
+/// let script = home.join("npm/lib/node_modules/@angular/cli/bin/ng.js");
+/// fs::create_dir_all(script.parent().unwrap()).unwrap();
+/// fs::write(&script, "").unwrap();
+/// fs::create_dir_all(home.join("npm/bin")).unwrap();
+/// symlink(&script, home.join("npm/bin/ng")).unwrap();
+/// let tool = detect_managed_tool(&home.join("npm/bin/ng")).unwrap();
+/// assert_eq!(tool.manager, PackageManager::Npm);
+/// assert_eq!(tool.package, "@angular/cli");
+/// assert_eq!(tool.paths, vec![home.join("npm/bin/ng"), home.join("npm/lib/node_modules/@angular/cli")]);
+///
+/// // `pipx` links binaries into the package's virtualenv.
+/// let venv_bin = home.join(".local/pipx/venvs/black/bin/black");
+/// fs::create_dir_all(venv_bin.parent().unwrap()).unwrap();
+/// fs::write(&venv_bin, "").unwrap();
+/// fs::create_dir_all(home.join(".local/bin")).unwrap();
+/// symlink(&venv_bin, home.join(".local/bin/black")).unwrap();
+/// let tool = detect_managed_tool(&home.join(".local/bin/black")).unwrap();
+/// assert_eq!((tool.manager, tool.package.as_str()), (PackageManager::Pipx, "black"));
+///
+/// // `gem` writes wrapper scripts naming their gem.
+/// fs::create_dir_all(home.join("gems/bin")).unwrap();
+/// fs::write(home.join("gems/bin/rails"), "require 'rubygems'\nload Gem.activate_bin_path('railties', 'rails', version)\n").unwrap();
+/// let tool = detect_managed_tool(&home.join("gems/bin/rails")).unwrap();
+/// assert_eq!((tool.manager, tool.package.as_str()), (PackageManager::Gem, "railties"));
+///
+/// // `go install` puts binaries in `GOBIN`.
+/// fs::create_dir_all(home.join("go/bin")).unwrap();
+/// fs::write(home.join("go/bin/gopls"), "").unwrap();
+/// let tool = detect_managed_tool(&home.join("go/bin/gopls")).unwrap();
+/// assert_eq!((tool.manager, tool.package.as_str()), (PackageManager::Go, "gopls"));
+///
+/// fs::write(home.join("gems/bin/plain"), "#!/bin/sh\n").unwrap();
+/// assert!(detect_managed_tool(&home.join("gems/bin/plain")).is_none());
+/// ```
+pub fn detect_managed_tool_in(binary: &Path, cargo_home: &Path, go_bin_dirs: &[PathBuf]) -> Option<ManagedTool> {
+    let name = binary.file_name()?.to_string_lossy().to_string();
+    let parent = binary.parent()?;
+    let target = fs::canonicalize(binary).unwrap_or_else(|_| binary.to_path_buf());
+
+    // cargo: binaries in `<cargo home>/bin`, listed in the install metadata.
+    if parent == cargo_home.join("bin")
+        && let Some((key, bins)) = find_cargo_package(cargo_home, &name)
+    {
+        return Some(ManagedTool {
+            manager: PackageManager::Cargo,
+            package: key,
+            paths: bins.iter().map(|bin| cargo_home.join("bin").join(bin)).collect(),
+        });
+    }
+    // npm and pipx: symlinks into the package folder or virtualenv.
+    for (manager, marker) in [(PackageManager::Npm, "node_modules"), (PackageManager::Pipx, "venvs")] {
+        if let Some((package, path)) = package_after(&target, marker)
+            && (manager == PackageManager::Npm || path.components().any(|c| c.as_os_str() == "pipx"))
+        {
+            return Some(ManagedTool {
+                manager,
+                package,
+                paths: vec![binary.to_path_buf(), path],
+            });
+        }
+    }
+    // go: binaries in `GOBIN`/`GOPATH/bin`, without any metadata.
+    if go_bin_dirs.iter().any(|dir| dir == parent) {
+        return Some(ManagedTool {
+            manager: PackageManager::Go,
+            package: name,
+            paths: vec![binary.to_path_buf()],
+        });
+    }
+    // gem: wrapper scripts calling `Gem.activate_bin_path('<gem>', ...)` or `Gem.bin_path('<gem>', ...)`.
+    let script = fs::read(&target).ok().filter(|content| content.len() < 64 * 1024)?;
+    let script = String::from_utf8_lossy(&script);
+    let gem = ["Gem.activate_bin_path(", "Gem.bin_path("].iter().find_map(|call| {
+        let args = &script[script.find(call)? + call.len()..];
+        let quote = args.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+        args[1..].split(quote).next().map(str::to_string)
+    })?;
+    Some(ManagedTool {
+        manager: PackageManager::Gem,
+        package: gem,
+        paths: vec![binary.to_path_buf()],
+    })
+}
+
+/// Uninstalls `tool` with its package manager (simulated in `dry_run`).
+///
+/// cargo and go installs are removed directly (updating cargo's metadata); npm, pipx and gem packages
+/// are removed with their own command through `runner`, which keeps their metadata consistent.
+pub fn uninstall_managed_tool(runner: &dyn CommandRunner, tool: &ManagedTool, dry_run: bool) -> io::Result<()> {
+    let command: Vec<&str> = match tool.manager {
+        PackageManager::Cargo => {
+            let bins: Vec<String> = tool
+                .paths
+                .iter()
+                .filter_map(|path| path.file_name())
+                .map(|bin| bin.to_string_lossy().to_string())
+                .collect();
+            log_debug!("Removing cargo package {}", tool.package.bright_white());
+            return remove_cargo_package(&cargo_home(), &tool.package, &bins, dry_run);
+        }
+        PackageManager::Go => return tool.paths.iter().try_for_each(|path| remove_path(path, dry_run)),
+        PackageManager::Npm => vec!["npm", "uninstall", "--global", &tool.package],
+        PackageManager::Pipx => vec!["pipx", "uninstall", &tool.package],
+        PackageManager::Gem => vec!["gem", "uninstall", "--all", "--executables", &tool.package],
+    };
+    if dry_run {
+        log_info!("Would run: {}", command.join(" ").bright_white());
+        return Ok(());
+    }
+    log_info!("Running: {}", command.join(" ").bright_white());
+    runner.run_checked(command[0], &command[1..]).map(|_| ())
+}
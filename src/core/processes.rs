@@ -0,0 +1,275 @@
+// This file finds and stops the processes of an app or tool being uninstalled. Removing a running app
+// leaves it half-removed, and it often recreates its caches right away, so the uninstaller checks for
+// processes whose executable lives inside what it removes, and quits them first when allowed to.
+// Processes are listed and signaled through `ProcessTable`, so this logic can be exercised with a mock.
+
+use crate::core::uninstall_plan::{PathCategory, UninstallPlan};
+use crate::utils::config::env_u64;
+use crate::{log_debug, log_info, log_warn};
+use colored::Colorize;
+use std::{
+    io,
+    path::PathBuf,
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Seconds a process gets to quit after `SIGTERM` before being killed, unless `OSX_QUIT_TIMEOUT` is set.
+const DEFAULT_QUIT_TIMEOUT_SECS: u64 = 10;
+
+/// A running process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunningProcess {
+    pub pid: u32,
+    /// Full path of the process' executable.
+    pub executable: PathBuf,
+}
+
+/// Lists and signals the processes of the system.
+pub trait ProcessTable: Send + Sync {
+    /// Lists the running processes.
+    fn list(&self) -> io::Result<Vec<RunningProcess>>;
+
+    /// Asks the process `pid` to quit (`SIGTERM`), or kills it (`SIGKILL`) when `force` is set.
+    fn signal(&self, pid: u32, force: bool) -> io::Result<()>;
+}
+
+/// The processes of the actual system, listed with `ps` and signaled with `kill(2)`.
+pub struct SystemProcesses;
+
+impl ProcessTable for SystemProcesses {
+    fn list(&self) -> io::Result<Vec<RunningProcess>> {
+        // On macOS, `comm` is the full path of the executable (arguments are left out, spaces are kept).
+        let output = Command::new("ps").args(["-axww", "-o", "pid=,comm="]).output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "`ps` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_ps(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn signal(&self, pid: u32, force: bool) -> io::Result<()> {
+        let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+        // SAFETY: `kill` has no memory-safety preconditions; failures are reported through `errno`.
+        if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+/// Parses the output of `ps -o pid=,comm=`: one process per line, its pid then its executable.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::processes::parse_ps;
+/// use std::path::PathBuf;
+///
+/// let processes = parse_ps("  412 /Applications/Visual Studio Code.app/Contents/MacOS/Electron\n   1 /sbin/launchd\nbogus\n");
+/// assert_eq!(processes.len(), 2);
+/// assert_eq!(processes[0].pid, 412);
+/// assert_eq!(processes[0].executable, PathBuf::from("/Applications/Visual Studio Code.app/Contents/MacOS/Electron"));
+/// ```
+pub fn parse_ps(output: &str) -> Vec<RunningProcess> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (pid, executable) = line.trim().split_once(char::is_whitespace)?;
+            Some(RunningProcess {
+                pid: pid.parse().ok()?,
+                executable: PathBuf::from(executable.trim()),
+            })
+        })
+        .collect()
+}
+
+/// Returns the processes of `processes` whose executable lives inside the bundles or binaries of `plan`.
+///
+/// Launchd jobs are left out: they are booted out when the plan is carried out.
+pub fn processes_in_plan(plan: &UninstallPlan, processes: &[RunningProcess]) -> Vec<RunningProcess> {
+    let roots: Vec<&PathBuf> = plan
+        .entries
+        .iter()
+        .filter(|entry| matches!(entry.category, PathCategory::Bundle | PathCategory::Binary))
+        .map(|entry| &entry.path)
+        .collect();
+    processes
+        .iter()
+        .filter(|process| roots.iter().any(|root| process.executable.starts_with(root)))
+        .cloned()
+        .collect()
+}
+
+/// Stops the `running` processes of `name` before it is uninstalled.
+///
+/// Without `force`, nothing is stopped and an error names the running processes. With `force`, they are
+/// asked to quit (`SIGTERM`), then killed (`SIGKILL`) if still running after `timeout`. Processes that
+/// exit before being signaled are fine.
+/// In `dry_run`, nothing is signaled and no error is returned: what would happen is logged instead.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::processes::{ProcessTable, RunningProcess, stop_processes};
+/// use std::{io, path::PathBuf, sync::Mutex, time::Duration};
+///
+/// // A process table whose processes ignore `SIGTERM`, recording the signals sent.
+/// struct Stubborn(Mutex<Vec<RunningProcess>>, Mutex<Vec<String>>);
+/// impl ProcessTable for Stubborn {
+///     fn list(&self) -> io::Result<Vec<RunningProcess>> {
+///         Ok(self.0.lock().unwrap().clone())
+///     }
+///     fn signal(&self, pid: u32, force: bool) -> io::Result<()> {
+///         self.1.lock().unwrap().push(format!("{} {}", if force { "KILL" } else { "TERM" }, pid));
+///         if force {
+///             self.0.lock().unwrap().retain(|p| p.pid != pid);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let slack = RunningProcess { pid: 42, executable: PathBuf::from("/Applications/Slack.app/Contents/MacOS/Slack") };
+/// let table = Stubborn(Mutex::new(vec![slack.clone()]), Mutex::new(Vec::new()));
+///
+/// // Without `--force`, the uninstall is aborted; a dry run only previews that.
+/// let error = stop_processes(&table, "Slack", &[slack.clone()], false, false, Duration::ZERO).unwrap_err();
+/// assert!(error.to_string().contains("--force"));
+/// stop_processes(&table, "Slack", &[slack.clone()], false, true, Duration::ZERO).unwrap();
+/// stop_processes(&table, "Slack", &[slack.clone()], true, true, Duration::ZERO).unwrap();
+/// assert!(table.1.lock().unwrap().is_empty());
+///
+/// stop_processes(&table, "Slack", &[slack.clone()], true, false, Duration::ZERO).unwrap();
+/// assert_eq!(*table.1.lock().unwrap(), vec!["TERM 42", "KILL 42"]);
+/// assert!(table.0.lock().unwrap().is_empty());
+/// ```
+pub fn stop_processes(
+    table: &dyn ProcessTable,
+    name: &str,
+    running: &[RunningProcess],
+    force: bool,
+    dry_run: bool,
+    timeout: Duration,
+) -> io::Result<()> {
+    if running.is_empty() {
+        return Ok(());
+    }
+    let listing = running
+        .iter()
+        .map(|process| format!("{} (pid {})", process.executable.display(), process.pid))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if dry_run {
+        if force {
+            log_info!("Would terminate: {}", listing.bright_white());
+        } else {
+            log_info!(
+                "Would abort: '{}' is running ({}). Quit it first, or pass {} to terminate it",
+                name.bright_yellow(),
+                listing,
+                "--force".bright_white()
+            );
+        }
+        return Ok(());
+    }
+    if !force {
+        return Err(io::Error::other(format!(
+            "'{}' is running: {}. Quit it first, or pass --force to terminate it",
+            name, listing
+        )));
+    }
+
+    log_info!("Quitting '{}': {}", name.bright_white(), listing);
+    for process in running {
+        signal_running(table, process.pid, false)?;
+    }
+    let remaining = wait_for_exit(table, running, timeout)?;
+    if remaining.is_empty() {
+        return Ok(());
+    }
+
+    log_warn!("'{}' did not quit after {}s, killing it", name, timeout.as_secs());
+    for process in &remaining {
+        signal_running(table, process.pid, true)?;
+    }
+    let remaining = wait_for_exit(table, &remaining, Duration::from_secs(1))?;
+    if remaining.is_empty() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "could not stop '{}' (pids {:?})",
+            name,
+            remaining.iter().map(|p| p.pid).collect::<Vec<_>>()
+        )))
+    }
+}
+
+/// Signals the process `pid` (see `ProcessTable::signal`). A process that already exited (`ESRCH`)
+/// needs no signal: that isn't an error.
+fn signal_running(table: &dyn ProcessTable, pid: u32, force: bool) -> io::Result<()> {
+    match table.signal(pid, force) {
+        Err(e) if e.raw_os_error() == Some(libc::ESRCH) => {
+            log_debug!("Process {} already exited", pid);
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Waits up to `timeout` for the `running` processes to exit, and returns those still running.
+fn wait_for_exit(
+    table: &dyn ProcessTable,
+    running: &[RunningProcess],
+    timeout: Duration,
+) -> io::Result<Vec<RunningProcess>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let listed = table.list()?;
+        let remaining: Vec<RunningProcess> = running.iter().filter(|p| listed.contains(p)).cloned().collect();
+        if remaining.is_empty() || Instant::now() >= deadline {
+            log_debug!("{} process(es) still running", remaining.len());
+            return Ok(remaining);
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+}
+
+/// Returns how long processes get to quit before being killed: `OSX_QUIT_TIMEOUT` seconds, 10 by default.
+pub fn quit_timeout() -> Duration {
+    Duration::from_secs(env_u64("OSX_QUIT_TIMEOUT", DEFAULT_QUIT_TIMEOUT_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// A process table whose processes exit right after being listed, before any signal reaches them.
+    struct Exited(Mutex<bool>);
+
+    impl ProcessTable for Exited {
+        fn list(&self) -> io::Result<Vec<RunningProcess>> {
+            Ok(Vec::new())
+        }
+
+        fn signal(&self, _pid: u32, _force: bool) -> io::Result<()> {
+            *self.0.lock().unwrap() = true;
+            Err(io::Error::from_raw_os_error(libc::ESRCH))
+        }
+    }
+
+    #[test]
+    fn processes_that_already_exited_are_stopped() {
+        let table = Exited(Mutex::new(false));
+        let process = RunningProcess {
+            pid: 42,
+            executable: PathBuf::from("/Applications/Slack.app/Contents/MacOS/Slack"),
+        };
+        assert!(stop_processes(&table, "Slack", &[process], true, false, Duration::ZERO).is_ok());
+        assert!(*table.0.lock().unwrap());
+    }
+}
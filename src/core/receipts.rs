@@ -0,0 +1,535 @@
+// This file reads macOS installer package receipts (`/var/db/receipts/<package id>.plist` and `.bom`)
+// to find every file a package installed. The BOM ("bill of materials") file is parsed in pure Rust,
+// so uninstalling a package removes what it installed, not only its receipt.
+//
+// A BOM file is a block store: a header points to an index of blocks and to named variables. The
+// `Paths` variable is a B-tree whose leaves list, for every installed path, its id, its parent's id,
+// its name and its type. All integers are big-endian.
+
+use crate::log_debug;
+use colored::Colorize;
+use plist::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Magic bytes at the start of every BOM file.
+const BOM_MAGIC: &[u8] = b"BOMStore";
+/// Extensions of bundles that are removed as a whole instead of file by file.
+const BUNDLE_EXTENSIONS: &[&str] = &[
+    "app", "appex", "bundle", "component", "framework", "kext", "mdimporter", "plugin", "prefPane", "qlgenerator",
+    "saver", "systemextension", "xpc",
+];
+
+/// Type of a path listed in a BOM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BomEntryKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+/// A path listed in a BOM, relative to the package's install prefix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BomEntry {
+    /// Relative path, without the leading `./`, e.g. `Applications/Foo.app/Contents/Info.plist`.
+    pub path: PathBuf,
+    pub kind: BomEntryKind,
+}
+
+/// A package receipt from `/var/db/receipts`.
+#[derive(Clone, Debug)]
+pub struct Receipt {
+    /// `PackageIdentifier`, e.g. `com.docker.pkg.Docker`.
+    pub id: String,
+    /// Absolute folder the package was installed into (`InstallPrefixPath`), usually `/`.
+    pub prefix: PathBuf,
+    /// Path of the receipt's `.bom` file.
+    pub bom: PathBuf,
+}
+
+/// What removing the files of a package involves.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReceiptRemoval {
+    /// Files, symlinks and whole bundles to remove.
+    pub paths: Vec<PathBuf>,
+    /// Folders created by the package, removed afterwards if they are empty, deepest first.
+    pub empty_dirs: Vec<PathBuf>,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid BOM: {}", message))
+}
+
+/// Reads a big-endian `u32` at `offset` of `data`.
+fn read_u32(data: &[u8], offset: usize) -> io::Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| invalid("truncated data"))
+}
+
+/// Reads a big-endian `u16` at `offset` of `data`.
+fn read_u16(data: &[u8], offset: usize) -> io::Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| invalid("truncated data"))
+}
+
+/// Parses the path list of a BOM file.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::receipts::{BomEntryKind, parse_bom};
+/// use std::path::Path;
+/// # // Builds a BOM holding a single `Paths` tree leaf with `(id, parent id, name, type)` entries.
+/// # fn bom(entries: &[(u32, u32, &str, u8)]) -> Vec<u8> {
+/// #     let mut blocks: Vec<Vec<u8>> = vec![vec![]];
+/// #     let mut add = |block: Vec<u8>| {
+/// #         blocks.push(block);
+/// #         (blocks.len() - 1) as u32
+/// #     };
+/// #     let mut leaf = [1u16.to_be_bytes(), (entries.len() as u16).to_be_bytes()].concat();
+/// #     leaf.extend([0; 8]);
+/// #     for (id, parent, name, kind) in entries {
+/// #         let info2 = add(vec![*kind, 0, 0, 0]);
+/// #         let info1 = add([id.to_be_bytes(), info2.to_be_bytes()].concat());
+/// #         let file = add([&parent.to_be_bytes()[..], name.as_bytes(), &[0]].concat());
+/// #         leaf.extend([info1.to_be_bytes(), file.to_be_bytes()].concat());
+/// #     }
+/// #     let leaf = add(leaf);
+/// #     let tree = add([&b"tree"[..], &1u32.to_be_bytes(), &leaf.to_be_bytes(), &[0; 8], &[0]].concat());
+/// #     let mut data = vec![0u8; 512];
+/// #     let mut index = (blocks.len() as u32).to_be_bytes().to_vec();
+/// #     for block in &blocks {
+/// #         index.extend([(data.len() as u32).to_be_bytes(), (block.len() as u32).to_be_bytes()].concat());
+/// #         data.extend(block);
+/// #     }
+/// #     let vars = [&1u32.to_be_bytes()[..], &tree.to_be_bytes(), &[5], b"Paths"].concat();
+/// #     let header = [1, blocks.len(), data.len(), index.len(), data.len() + index.len(), vars.len()];
+/// #     data[..8].copy_from_slice(b"BOMStore");
+/// #     for (i, value) in header.iter().enumerate() {
+/// #         data[8 + i * 4..12 + i * 4].copy_from_slice(&(*value as u32).to_be_bytes());
+/// #     }
+/// #     data.extend(index);
+/// #     data.extend(vars);
+/// #     data
+/// # }
+///
+/// // A package installing `Foo.app` and a `foo` symlink in `/usr/local/bin` (types: 1 file, 2 folder, 3 symlink).
+/// let data = bom(&[
+///     (1, 0, ".", 2),
+///     (2, 1, "Applications", 2),
+///     (3, 2, "Foo.app", 2),
+///     (4, 3, "Info.plist", 1),
+///     (5, 1, "usr", 2),
+///     (6, 5, "local", 2),
+///     (7, 6, "bin", 2),
+///     (8, 7, "foo", 3),
+/// ]);
+/// let entries = parse_bom(&data).unwrap();
+/// assert_eq!(entries.len(), 7);
+/// assert_eq!(entries[2].path, Path::new("Applications/Foo.app/Info.plist"));
+/// assert_eq!(entries[2].kind, BomEntryKind::File);
+/// assert_eq!(entries[6].path, Path::new("usr/local/bin/foo"));
+/// assert_eq!(entries[6].kind, BomEntryKind::Symlink);
+///
+/// assert!(parse_bom(b"not a bom").is_err());
+/// ```
+pub fn parse_bom(data: &[u8]) -> io::Result<Vec<BomEntry>> {
+    if !data.starts_with(BOM_MAGIC) {
+        return Err(invalid("missing BOMStore magic"));
+    }
+    let block_count = read_u32(data, 12)? as usize;
+    let index_offset = read_u32(data, 16)? as usize;
+    let vars_offset = read_u32(data, 24)? as usize;
+
+    // The block table: `count` pointers of (address, length).
+    let pointer_count = read_u32(data, index_offset)? as usize;
+    let block = |index: u32| -> io::Result<&[u8]> {
+        let index = index as usize;
+        if index >= pointer_count {
+            return Err(invalid("block index out of range"));
+        }
+        let address = read_u32(data, index_offset + 4 + index * 8)? as usize;
+        let length = read_u32(data, index_offset + 8 + index * 8)? as usize;
+        data.get(address..address + length).ok_or_else(|| invalid("block out of range"))
+    };
+
+    // Named variables: find the `Paths` tree.
+    let mut paths_block = None;
+    let mut offset = vars_offset + 4;
+    for _ in 0..read_u32(data, vars_offset)? {
+        let index = read_u32(data, offset)?;
+        let length = *data.get(offset + 4).ok_or_else(|| invalid("truncated variables"))? as usize;
+        let name = data.get(offset + 5..offset + 5 + length).ok_or_else(|| invalid("truncated variables"))?;
+        if name == b"Paths" {
+            paths_block = Some(index);
+        }
+        offset += 5 + length;
+    }
+    let tree = block(paths_block.ok_or_else(|| invalid("no Paths variable"))?)?;
+    if !tree.starts_with(b"tree") {
+        return Err(invalid("Paths is not a tree"));
+    }
+
+    // Walk down to the leftmost leaf, then along the leaves through their `forward` links.
+    let mut node_index = read_u32(tree, 8)?;
+    let mut nodes = Vec::new();
+    for _ in 0..=block_count {
+        let node = block(node_index)?;
+        if read_u16(node, 0)? != 0 {
+            break;
+        }
+        node_index = read_u32(node, 12)?;
+    }
+    for _ in 0..=block_count {
+        let node = block(node_index)?;
+        nodes.push(node);
+        node_index = read_u32(node, 4)?;
+        if node_index == 0 {
+            break;
+        }
+    }
+
+    // Every entry: its id, its parent's id, its name and its type.
+    let mut files: Vec<(u32, u32, String, BomEntryKind)> = Vec::new();
+    for node in nodes {
+        for i in 0..read_u16(node, 2)? as usize {
+            let info1 = block(read_u32(node, 12 + i * 8)?)?;
+            let file = block(read_u32(node, 16 + i * 8)?)?;
+            let kind = match block(read_u32(info1, 4)?)?.first() {
+                Some(1) => BomEntryKind::File,
+                Some(2) => BomEntryKind::Directory,
+                Some(3) => BomEntryKind::Symlink,
+                _ => BomEntryKind::Other,
+            };
+            let name = file.get(4..).ok_or_else(|| invalid("truncated file name"))?;
+            let name = name.split(|b| *b == 0).next().unwrap_or_default();
+            files.push((read_u32(info1, 0)?, read_u32(file, 0)?, String::from_utf8_lossy(name).to_string(), kind));
+        }
+    }
+
+    // Rebuild the full paths from the parent links.
+    let by_id: HashMap<u32, (u32, &str)> = files.iter().map(|(id, parent, name, _)| (*id, (*parent, name.as_str()))).collect();
+    let full_path = |mut id: u32| -> PathBuf {
+        let mut components = Vec::new();
+        for _ in 0..=files.len() {
+            match by_id.get(&id) {
+                Some((parent, name)) if *name != "." => {
+                    components.push(*name);
+                    id = *parent;
+                }
+                _ => break,
+            }
+        }
+        components.iter().rev().collect()
+    };
+    Ok(files
+        .iter()
+        .filter(|(_, _, name, _)| name != ".")
+        .map(|(id, _, _, kind)| BomEntry {
+            path: full_path(*id),
+            kind: *kind,
+        })
+        .collect())
+}
+
+/// Returns `true` for folders shared with the system or other software, which are never removed
+/// even when empty: top-level folders (`/Applications`, `/Library/Application Support`...),
+/// `/usr/local/<folder>`, man page folders and anything under `/System`.
+fn is_shared_dir(dir: &Path) -> bool {
+    dir.components().count() <= 3
+        || dir.starts_with("/System")
+        || (dir.starts_with("/usr/local") && dir.components().count() <= 4)
+        || dir.starts_with("/usr/local/share/man")
+}
+
+/// Decides what to remove for the BOM `entries` of a package installed into `prefix`.
+///
+/// Bundles (`.app`, `.framework`, `.plugin`...) the package created, i.e. whose own `Info.plist` it
+/// installed, are removed as a whole; other files one by one. A bundle the package only installed into
+/// (e.g. a plug-in inside another vendor's app) is left in place, with every folder it contains.
+/// Folders are only removed once empty, and never when they are shared (see `is_shared_dir`).
+/// Paths under `/System`, and paths also installed by other packages (`other_packages`, see
+/// `paths_of_other_receipts`), are left alone.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::receipts::{BomEntry, BomEntryKind, plan_receipt_removal};
+/// use std::{collections::HashSet, path::{Path, PathBuf}};
+///
+/// let entry = |path: &str, kind| BomEntry { path: PathBuf::from(path), kind };
+/// let entries = [
+///     entry("Applications", BomEntryKind::Directory),
+///     entry("Applications/Foo.app", BomEntryKind::Directory),
+///     entry("Applications/Foo.app/Contents/Info.plist", BomEntryKind::File),
+///     entry("Library/Application Support/Foo", BomEntryKind::Directory),
+///     entry("Library/Application Support/Foo/Plugins", BomEntryKind::Directory),
+///     entry("Library/Application Support/Foo/Plugins/sync.dylib", BomEntryKind::File),
+///     entry("usr/local/bin", BomEntryKind::Directory),
+///     entry("usr/local/bin/foo", BomEntryKind::Symlink),
+/// ];
+///
+/// let removal = plan_receipt_removal(Path::new("/"), &entries, &HashSet::new());
+/// assert_eq!(removal.paths, vec![
+///     PathBuf::from("/Applications/Foo.app"),
+///     PathBuf::from("/Library/Application Support/Foo/Plugins/sync.dylib"),
+///     PathBuf::from("/usr/local/bin/foo"),
+/// ]);
+/// assert_eq!(removal.empty_dirs, vec![
+///     PathBuf::from("/Library/Application Support/Foo/Plugins"),
+///     PathBuf::from("/Library/Application Support/Foo"),
+/// ]);
+///
+/// // A second package installed into the same prefix also lists the plugin and the `foo` symlink:
+/// // those stay, as the other package still needs them.
+/// let other = [
+///     entry("Library/Application Support/Foo/Plugins/sync.dylib", BomEntryKind::File),
+///     entry("usr/local/bin/foo", BomEntryKind::Symlink),
+/// ];
+/// let other_packages: HashSet<PathBuf> = other.iter().map(|e| Path::new("/").join(&e.path)).collect();
+/// let removal = plan_receipt_removal(Path::new("/"), &entries, &other_packages);
+/// assert_eq!(removal.paths, vec![PathBuf::from("/Applications/Foo.app")]);
+/// ```
+pub fn plan_receipt_removal(prefix: &Path, entries: &[BomEntry], other_packages: &HashSet<PathBuf>) -> ReceiptRemoval {
+    let absolute: Vec<(PathBuf, BomEntryKind)> = entries
+        .iter()
+        .map(|entry| (prefix.join(&entry.path), entry.kind))
+        .filter(|(path, _)| !path.starts_with("/System"))
+        .collect();
+    let is_bundle = |path: &Path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| BUNDLE_EXTENSIONS.contains(&ext))
+    };
+    let all_bundles: Vec<&PathBuf> = absolute
+        .iter()
+        .filter(|(path, kind)| *kind == BomEntryKind::Directory && is_bundle(path))
+        .map(|(path, _)| path)
+        .collect();
+    // The innermost bundle holding `path`, if any.
+    let bundle_of = |path: &Path| {
+        all_bundles
+            .iter()
+            .filter(|bundle| path != **bundle && path.starts_with(bundle))
+            .max_by_key(|bundle| bundle.components().count())
+            .copied()
+    };
+    let (bundles, foreign_bundles): (Vec<&PathBuf>, Vec<&PathBuf>) = all_bundles.iter().partition(|bundle| {
+        absolute.iter().any(|(path, kind)| {
+            *kind == BomEntryKind::File
+                && path.file_name().is_some_and(|name| name == "Info.plist")
+                && bundle_of(path) == Some(**bundle)
+        })
+    });
+    let in_bundle = |path: &Path| bundles.iter().any(|bundle| path != *bundle && path.starts_with(bundle));
+    let in_foreign_bundle = |path: &Path| foreign_bundles.iter().any(|bundle| path.starts_with(bundle));
+
+    let mut removal = ReceiptRemoval::default();
+    for (path, kind) in &absolute {
+        // Files of a bundle go with it; shared paths (and shared bundles as a whole) stay.
+        if in_bundle(path) || other_packages.contains(path) {
+            continue;
+        }
+        match kind {
+            BomEntryKind::Directory if !bundles.contains(&path) => {
+                if !is_shared_dir(path) && !in_foreign_bundle(path) {
+                    removal.empty_dirs.push(path.clone());
+                }
+            }
+            _ => removal.paths.push(path.clone()),
+        }
+    }
+    removal.empty_dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+    removal
+}
+
+/// Reads the receipt plist `plist_path` (e.g. `/var/db/receipts/com.example.pkg.plist`).
+/// Its BOM is the `.bom` file next to it.
+pub fn read_receipt(plist_path: &Path) -> io::Result<Receipt> {
+    let receipt = Value::from_file(plist_path)
+        .map_err(io::Error::other)?
+        .into_dictionary()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "receipt is not a dictionary"))?;
+    let string = |key: &str| receipt.get(key).and_then(Value::as_string).map(str::to_string);
+    let id = string("PackageIdentifier").unwrap_or_else(|| {
+        plist_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    let prefix = string("InstallPrefixPath").unwrap_or_default();
+
+    Ok(Receipt {
+        id,
+        prefix: Path::new("/").join(prefix.trim_start_matches('/')),
+        bom: plist_path.with_extension("bom"),
+    })
+}
+
+/// Returns `true` for receipts of Apple packages (`com.apple.*`), which an uninstall never touches.
+pub fn is_apple_receipt(file_name: &str) -> bool {
+    file_name.to_lowercase().starts_with("com.apple.")
+}
+
+/// Returns every path installed by the packages whose receipts are in `receipt_dirs`,
+/// except the receipts in `excluded` (the packages being uninstalled).
+///
+/// These paths are shared with other packages, so removing a package must leave them in place.
+pub fn paths_of_other_receipts(receipt_dirs: &[&Path], excluded: &[PathBuf]) -> HashSet<PathBuf> {
+    let mut paths = HashSet::new();
+    for dir in receipt_dirs {
+        let Ok(entries) = fs::read_dir(dir) else { continue };
+        for plist in entries.flatten().map(|entry| entry.path()) {
+            if plist.extension().is_none_or(|ext| ext != "plist") || excluded.contains(&plist) {
+                continue;
+            }
+            let Ok(receipt) = read_receipt(&plist) else { continue };
+            if let Ok(entries) = fs::read(&receipt.bom).and_then(|data| parse_bom(&data)) {
+                paths.extend(entries.iter().map(|entry| receipt.prefix.join(&entry.path)));
+            }
+        }
+    }
+    paths
+}
+
+/// Returns what removing the package of the receipt `plist_path` involves, leaving alone the paths
+/// other packages installed too (`other_packages`).
+/// Unreadable receipts or BOMs give an empty removal: only the receipt itself is then forgotten.
+pub fn receipt_removal(plist_path: &Path, other_packages: &HashSet<PathBuf>) -> ReceiptRemoval {
+    let result = read_receipt(plist_path).and_then(|receipt| {
+        let entries = parse_bom(&fs::read(&receipt.bom)?)?;
+        log_debug!(
+            "Package {} installed {} path(s) into {}",
+            receipt.id.bright_white(),
+            entries.len(),
+            receipt.prefix.display()
+        );
+        Ok(plan_receipt_removal(&receipt.prefix, &entries, other_packages))
+    });
+    match result {
+        Ok(removal) => removal,
+        Err(e) => {
+            log_debug!("Could not read the files installed by {}: {}", plist_path.display(), e);
+            ReceiptRemoval::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    fn entry(path: &str, kind: BomEntryKind) -> BomEntry {
+        BomEntry {
+            path: PathBuf::from(path),
+            kind,
+        }
+    }
+
+    /// Builds a BOM holding a single `Paths` tree leaf with `(id, parent id, name, type)` entries.
+    fn bom(entries: &[(u32, u32, &str, u8)]) -> Vec<u8> {
+        let mut blocks: Vec<Vec<u8>> = vec![vec![]];
+        let mut add = |block: Vec<u8>| {
+            blocks.push(block);
+            (blocks.len() - 1) as u32
+        };
+        let mut leaf = [1u16.to_be_bytes(), (entries.len() as u16).to_be_bytes()].concat();
+        leaf.extend([0; 8]);
+        for (id, parent, name, kind) in entries {
+            let info2 = add(vec![*kind, 0, 0, 0]);
+            let info1 = add([id.to_be_bytes(), info2.to_be_bytes()].concat());
+            let file = add([&parent.to_be_bytes()[..], name.as_bytes(), &[0]].concat());
+            leaf.extend([info1.to_be_bytes(), file.to_be_bytes()].concat());
+        }
+        let leaf = add(leaf);
+        let tree = add([&b"tree"[..], &1u32.to_be_bytes(), &leaf.to_be_bytes(), &[0; 8], &[0]].concat());
+        let mut data = vec![0u8; 512];
+        let mut index = (blocks.len() as u32).to_be_bytes().to_vec();
+        for block in &blocks {
+            index.extend([(data.len() as u32).to_be_bytes(), (block.len() as u32).to_be_bytes()].concat());
+            data.extend(block);
+        }
+        let vars = [&1u32.to_be_bytes()[..], &tree.to_be_bytes(), &[5], b"Paths"].concat();
+        let header = [1, blocks.len(), data.len(), index.len(), data.len() + index.len(), vars.len()];
+        data[..8].copy_from_slice(BOM_MAGIC);
+        for (i, value) in header.iter().enumerate() {
+            data[8 + i * 4..12 + i * 4].copy_from_slice(&(*value as u32).to_be_bytes());
+        }
+        data.extend(index);
+        data.extend(vars);
+        data
+    }
+
+    /// Writes the receipt `id` (plist and BOM) into `dir`, for a package installed into `prefix`.
+    fn write_receipt(dir: &Path, id: &str, prefix: &Path, entries: &[(u32, u32, &str, u8)]) -> PathBuf {
+        let plist = dir.join(format!("{}.plist", id));
+        fs::write(
+            &plist,
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict>
+    <key>PackageIdentifier</key><string>{}</string>
+    <key>InstallPrefixPath</key><string>{}</string>
+</dict></plist>"#,
+                id,
+                prefix.display()
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join(format!("{}.bom", id)), bom(entries)).unwrap();
+        plist
+    }
+
+    #[test]
+    fn bundles_the_package_did_not_create_are_kept() {
+        // A plug-in installer putting `Sync.plugin` into another vendor's app.
+        let entries = [
+            entry("Applications/Other.app", BomEntryKind::Directory),
+            entry("Applications/Other.app/Contents", BomEntryKind::Directory),
+            entry("Applications/Other.app/Contents/PlugIns", BomEntryKind::Directory),
+            entry("Applications/Other.app/Contents/PlugIns/Sync.plugin", BomEntryKind::Directory),
+            entry("Applications/Other.app/Contents/PlugIns/Sync.plugin/Contents", BomEntryKind::Directory),
+            entry("Applications/Other.app/Contents/PlugIns/Sync.plugin/Contents/Info.plist", BomEntryKind::File),
+        ];
+        let removal = plan_receipt_removal(Path::new("/"), &entries, &HashSet::new());
+        assert_eq!(removal.paths, vec![PathBuf::from("/Applications/Other.app/Contents/PlugIns/Sync.plugin")]);
+        assert!(removal.empty_dirs.is_empty());
+    }
+
+    #[test]
+    fn files_shared_with_another_receipt_are_kept() {
+        let fixture = env::temp_dir().join(format!("osx-test-shared-receipts-{}", process::id()));
+        let _ = fs::remove_dir_all(&fixture);
+        let receipts = fixture.join("receipts");
+        fs::create_dir_all(&receipts).unwrap();
+
+        // Both packages install `lib/libsync.dylib`; only `com.example.foo` installs `bin/foo`.
+        let foo = write_receipt(
+            &receipts,
+            "com.example.foo",
+            &fixture,
+            &[(1, 0, ".", 2), (2, 1, "bin", 2), (3, 2, "foo", 1), (4, 1, "lib", 2), (5, 4, "libsync.dylib", 1)],
+        );
+        write_receipt(
+            &receipts,
+            "com.example.bar",
+            &fixture,
+            &[(1, 0, ".", 2), (2, 1, "lib", 2), (3, 2, "libsync.dylib", 1)],
+        );
+
+        let other_packages = paths_of_other_receipts(&[receipts.as_path()], std::slice::from_ref(&foo));
+        assert!(other_packages.contains(&fixture.join("lib/libsync.dylib")));
+        assert!(!other_packages.contains(&fixture.join("bin/foo")));
+
+        let removal = receipt_removal(&foo, &other_packages);
+        assert_eq!(removal.paths, vec![fixture.join("bin/foo")]);
+        let _ = fs::remove_dir_all(&fixture);
+    }
+}
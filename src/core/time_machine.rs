@@ -0,0 +1,208 @@
+// This file manages local Time Machine snapshots for the `snapshots` subcommand, through `tmutil`.
+// APFS keeps the blocks of deleted files alive while a local snapshot references them, so space
+// "freed" by `clean-my-mac` may only become purgeable: Finder counts it as available, but it is only
+// released when macOS thins the snapshots (under disk pressure, or after 24 hours).
+
+use crate::utils::command::CommandRunner;
+use crate::{log_debug, log_error, log_info};
+use colored::Colorize;
+use std::io;
+use tabled::{Table, Tabled, settings::Style};
+
+/// Prefix of the names of local snapshots taken by Time Machine.
+const SNAPSHOT_PREFIX: &str = "com.apple.TimeMachine.";
+/// Urgency passed to `tmutil thinlocalsnapshots`, from 1 (lowest) to 4 (highest).
+const THIN_URGENCY: &str = "4";
+
+/// A local Time Machine snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalSnapshot {
+    /// Full snapshot name, e.g. `com.apple.TimeMachine.2024-05-01-102030.local`.
+    pub name: String,
+    /// Snapshot date as used by `tmutil deletelocalsnapshots`, e.g. `2024-05-01-102030`.
+    pub date: String,
+}
+
+/// Represents a row of the snapshots table.
+#[derive(Tabled)]
+struct SnapshotEntry {
+    #[tabled(rename = "Snapshot")]
+    name: String,
+    #[tabled(rename = "Date")]
+    date: String,
+    #[tabled(rename = "Action")]
+    action: String,
+}
+
+/// Parses the output of `tmutil listlocalsnapshots <volume>`.
+///
+/// Both the current format (names ending in `.local`, after a `Snapshots for disk` header) and
+/// the older format (bare names) are supported; snapshots not taken by Time Machine are ignored.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::time_machine::parse_local_snapshots;
+///
+/// let output = "Snapshots for disk /:
+/// com.apple.TimeMachine.2024-05-01-102030.local
+/// com.apple.TimeMachine.2024-05-02-112233.local
+/// com.apple.os.update-4B2F1C
+/// ";
+/// let snapshots = parse_local_snapshots(output);
+/// assert_eq!(snapshots.len(), 2);
+/// assert_eq!(snapshots[0].date, "2024-05-01-102030");
+/// assert_eq!(snapshots[1].name, "com.apple.TimeMachine.2024-05-02-112233.local");
+///
+/// // Output of older macOS versions.
+/// let snapshots = parse_local_snapshots("com.apple.TimeMachine.2019-10-01-080000\n");
+/// assert_eq!(snapshots[0].date, "2019-10-01-080000");
+///
+/// assert!(parse_local_snapshots("Snapshots for disk /:\n").is_empty());
+/// ```
+pub fn parse_local_snapshots(output: &str) -> Vec<LocalSnapshot> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            let date = line.strip_prefix(SNAPSHOT_PREFIX)?;
+            let date = date.strip_suffix(".local").unwrap_or(date);
+            Some(LocalSnapshot {
+                name: line.to_string(),
+                date: date.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Lists the local Time Machine snapshots of `volume`.
+pub fn list_local_snapshots(runner: &dyn CommandRunner, volume: &str) -> io::Result<Vec<LocalSnapshot>> {
+    let output = runner.run_checked("tmutil", &["listlocalsnapshots", volume])?;
+    Ok(parse_local_snapshots(&output))
+}
+
+/// Deletes the local snapshot taken at `date` (simulated in `dry_run`).
+pub fn delete_local_snapshot(runner: &dyn CommandRunner, date: &str, dry_run: bool) -> io::Result<()> {
+    if dry_run {
+        return Ok(());
+    }
+    runner.run_checked("tmutil", &["deletelocalsnapshots", date]).map(|_| ())
+}
+
+/// Asks macOS to thin the local snapshots of `volume` until `bytes` are purged (simulated in `dry_run`).
+pub fn thin_local_snapshots(runner: &dyn CommandRunner, volume: &str, bytes: u64, dry_run: bool) -> io::Result<()> {
+    if dry_run {
+        return Ok(());
+    }
+    let bytes = bytes.to_string();
+    runner
+        .run_checked("tmutil", &["thinlocalsnapshots", volume, &bytes, THIN_URGENCY])
+        .map(|_| ())
+}
+
+/// Logs why freed space may not show up in Finder yet, when local snapshots exist.
+/// Used at the end of `clean-my-mac`; nothing is logged if `tmutil` is unavailable.
+pub fn explain_purgeable_space(runner: &dyn CommandRunner) {
+    match list_local_snapshots(runner, "/") {
+        Ok(snapshots) if !snapshots.is_empty() => {
+            log_info!(
+                "🕰️  {} local Time Machine snapshot(s) exist. Space freed from files they contain becomes {}: \
+                 Finder counts it as available, but the disk only gets it back once macOS thins the snapshots. \
+                 Run {} to release it now.",
+                snapshots.len(),
+                "purgeable".bright_yellow(),
+                "osx snapshots --thin <bytes>".bright_white()
+            );
+        }
+        Ok(_) => {}
+        Err(e) => log_debug!("Could not list local snapshots: {}", e),
+    }
+}
+
+/// Runs the `snapshots` subcommand.
+///
+/// Lists the local Time Machine snapshots of `volume`, deletes the snapshots whose date is in
+/// `delete` (or all of them with `delete_all`), and thins them by `thin` bytes when given.
+pub fn run_snapshots(
+    runner: &dyn CommandRunner,
+    volume: &str,
+    delete: &[String],
+    delete_all: bool,
+    thin: Option<u64>,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let snapshots = list_local_snapshots(runner, volume)?;
+    let mut failures = 0;
+
+    let mut entries = Vec::new();
+    for snapshot in &snapshots {
+        let action = if delete_all || delete.contains(&snapshot.date) {
+            match delete_local_snapshot(runner, &snapshot.date, dry_run) {
+                Ok(_) if dry_run => "Would delete".to_string(),
+                Ok(_) => "Deleted".to_string(),
+                Err(e) => {
+                    log_error!("❌ Failed to delete snapshot {}: {}", snapshot.name, e);
+                    failures += 1;
+                    format!("Failed: {}", e)
+                }
+            }
+        } else {
+            String::new()
+        };
+        entries.push(SnapshotEntry {
+            name: snapshot.name.clone(),
+            date: snapshot.date.clone(),
+            action,
+        });
+    }
+    for date in delete.iter().filter(|d| !snapshots.iter().any(|s| &s.date == *d)) {
+        log_error!("No local snapshot dated '{}' found.", date);
+        failures += 1;
+    }
+
+    if entries.is_empty() {
+        log_info!("No local Time Machine snapshots found on {}", volume.white().dimmed());
+    } else {
+        println!(
+            "\n{}\n",
+            format!("🕰️  Local Time Machine Snapshots ({})", volume).bold().underline().purple()
+        );
+        println!("{}", Table::new(&entries).with(Style::modern()));
+        eprintln!();
+        log_info!(
+            "ℹ️  Snapshot sizes aren't reported by macOS. While they exist, space freed by deleting files is \
+             only {} (counted as available by Finder) until the snapshots are thinned or deleted.",
+            "purgeable".bright_yellow()
+        );
+    }
+
+    if let Some(bytes) = thin {
+        match thin_local_snapshots(runner, volume, bytes, dry_run) {
+            Ok(_) if dry_run => log_info!("Would thin local snapshots of {} by {} bytes", volume, bytes),
+            Ok(_) => log_info!("{}: asked macOS to purge {} bytes of local snapshots", "Thinned".bright_green(), bytes),
+            Err(e) => {
+                log_error!("❌ Failed to thin local snapshots: {}", e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} snapshot operation(s) failed", failures).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::command::testing::RecordingRunner;
+
+    #[test]
+    fn thin_local_snapshots_runs_tmutil_outside_dry_run() {
+        let runner = RecordingRunner::succeeding();
+        thin_local_snapshots(&runner, "/", 10_000_000_000, false).unwrap();
+        thin_local_snapshots(&runner, "/", 10_000_000_000, true).unwrap();
+        assert_eq!(runner.commands(), vec!["tmutil thinlocalsnapshots / 10000000000 4"]);
+    }
+}
@@ -0,0 +1,537 @@
+// This file inventories developer toolchain versions installed by version managers
+// (rustup, nvm, pyenv, rbenv and SDKMAN!) for the `toolchains` subcommand and the report-only
+// `ToolchainsCleaner`. A version is kept when it is the manager's default or when a project pins it
+// (`rust-toolchain.toml`, `.nvmrc`, `.python-version`, ...); every other version can be pruned.
+
+use crate::core::cleaners::{format_age, format_bytes};
+use crate::utils::config::env_list;
+use crate::utils::filesystem::{calculate_dir_size, remove_path};
+use crate::{log_debug, log_error, log_info};
+use colored::Colorize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tabled::{Table, Tabled, settings::Style};
+use walkdir::WalkDir;
+
+/// Environment variable holding the project roots scanned for pin files, separated by commas.
+pub const PROJECT_ROOTS_ENV: &str = "OSX_PROJECT_ROOTS";
+/// Project roots scanned when `OSX_PROJECT_ROOTS` is not set, relative to the home directory.
+const DEFAULT_PROJECT_ROOTS: &[&str] = &["Developer", "Projects", "Code", "src", "work", "dev"];
+/// How deep project roots are scanned for pin files.
+const PROJECT_SCAN_DEPTH: usize = 5;
+/// Folders never scanned for pin files: dependencies and build outputs.
+const SKIPPED_PROJECT_DIRS: &[&str] = &["node_modules", ".git", "target", "vendor", ".venv", "venv", "build", "dist"];
+/// Target triple prefixes that end the channel part of a rustup toolchain name.
+const RUST_TRIPLE_MARKERS: &[&str] = &["-aarch64-", "-x86_64-", "-arm64-", "-i686-"];
+
+/// A version manager whose installed versions are inventoried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ToolchainManager {
+    Rustup,
+    Nvm,
+    Pyenv,
+    Rbenv,
+    Sdkman,
+}
+
+impl ToolchainManager {
+    /// Returns the user-facing name of the manager.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToolchainManager::Rustup => "rustup",
+            ToolchainManager::Nvm => "nvm",
+            ToolchainManager::Pyenv => "pyenv",
+            ToolchainManager::Rbenv => "rbenv",
+            ToolchainManager::Sdkman => "sdkman",
+        }
+    }
+}
+
+/// An installed toolchain version.
+#[derive(Clone, Debug)]
+pub struct Toolchain {
+    pub manager: ToolchainManager,
+    /// Version as named by the manager, e.g. `1.75.0-aarch64-apple-darwin`, `v20.11.0` or `java/21.0.2-tem`.
+    pub version: String,
+    pub path: PathBuf,
+    /// Last time one of the toolchain's executables was accessed.
+    pub last_used: Option<SystemTime>,
+    /// Why the version is kept (e.g. "default", "pinned by ~/Projects/app/.nvmrc"), or `None` if it is unused.
+    pub keep_reason: Option<String>,
+}
+
+/// A version requested by a default setting or a pin file.
+struct Requested {
+    manager: ToolchainManager,
+    /// For SDKMAN!, the candidate name (e.g. `java`); empty for other managers.
+    candidate: String,
+    version: String,
+    reason: String,
+}
+
+/// Represents a row of the toolchains table.
+#[derive(Tabled)]
+struct ToolchainEntry {
+    #[tabled(rename = "Manager")]
+    manager: String,
+    #[tabled(rename = "Version")]
+    version: String,
+    #[tabled(rename = "Size")]
+    size: String,
+    #[tabled(rename = "Last Used")]
+    last_used: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+/// Returns `true` if the installed version `installed` satisfies the requested version `requested`.
+///
+/// A leading `v` is ignored, and a request matches every version it is a prefix of, component-wise:
+/// `20` matches `v20.11.0` and `3.12` matches `3.12.1`, but `3.1` does not match `3.12.1`.
+/// Rustup toolchain names are compared without their target triple.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::toolchains::version_matches;
+///
+/// assert!(version_matches("v20.11.0", "20"));
+/// assert!(version_matches("v20.11.0", "v20.11.0"));
+/// assert!(version_matches("3.12.1", "3.12"));
+/// assert!(!version_matches("3.12.1", "3.1"));
+/// assert!(version_matches("stable-aarch64-apple-darwin", "stable"));
+/// assert!(version_matches("1.75.0-x86_64-apple-darwin", "1.75"));
+/// assert!(!version_matches("nightly-2024-01-01-aarch64-apple-darwin", "nightly"));
+/// assert!(version_matches("21.0.2-tem", "21.0.2-tem"));
+/// ```
+pub fn version_matches(installed: &str, requested: &str) -> bool {
+    let installed = rust_channel(installed.trim()).trim_start_matches('v').to_lowercase();
+    let requested = rust_channel(requested.trim()).trim_start_matches('v').to_lowercase();
+    !requested.is_empty()
+        && (installed == requested
+            || installed
+                .strip_prefix(&requested)
+                .is_some_and(|rest| rest.starts_with('.')))
+}
+
+/// Strips the target triple from a rustup toolchain name: `stable-aarch64-apple-darwin` → `stable`.
+fn rust_channel(name: &str) -> &str {
+    RUST_TRIPLE_MARKERS
+        .iter()
+        .filter_map(|marker| name.find(marker))
+        .min()
+        .map_or(name, |end| &name[..end])
+}
+
+/// Sort key of a version: its numbers, in order, so `v20.11.0` sorts after `v9.8.0`.
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Returns the root folder of a manager, honoring its usual environment variable.
+fn manager_root(var: &str, default: &str) -> PathBuf {
+    env::var(var).map(PathBuf::from).unwrap_or_else(|_| {
+        PathBuf::from(format!("{}/{}", env::var("HOME").unwrap_or_default(), default))
+    })
+}
+
+/// Lists the installed versions of every manager found, without their keep reason.
+fn installed_toolchains() -> Vec<Toolchain> {
+    let mut found: Vec<(ToolchainManager, String, PathBuf)> = Vec::new();
+    let mut list = |manager: ToolchainManager, dir: PathBuf, prefix: &str| {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Symlinks are aliases (pyenv virtualenvs, SDKMAN!'s `current`), not installed versions.
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            if is_dir && !name.starts_with('.') && name != "current" {
+                found.push((manager, format!("{}{}", prefix, name), entry.path()));
+            }
+        }
+    };
+
+    list(ToolchainManager::Rustup, manager_root("RUSTUP_HOME", ".rustup").join("toolchains"), "");
+    list(ToolchainManager::Nvm, manager_root("NVM_DIR", ".nvm").join("versions/node"), "");
+    list(ToolchainManager::Pyenv, manager_root("PYENV_ROOT", ".pyenv").join("versions"), "");
+    list(ToolchainManager::Rbenv, manager_root("RBENV_ROOT", ".rbenv").join("versions"), "");
+    let candidates = manager_root("SDKMAN_DIR", ".sdkman").join("candidates");
+    if let Ok(entries) = fs::read_dir(&candidates) {
+        for entry in entries.flatten() {
+            let candidate = entry.file_name().to_string_lossy().to_string();
+            list(ToolchainManager::Sdkman, entry.path(), &format!("{}/", candidate));
+        }
+    }
+
+    found
+        .into_iter()
+        .map(|(manager, version, path)| Toolchain {
+            last_used: last_used(&path),
+            manager,
+            version,
+            path,
+            keep_reason: None,
+        })
+        .collect()
+}
+
+/// Returns the latest access time of the executables in the toolchain's `bin` folder,
+/// falling back to the modification time of the toolchain itself.
+fn last_used(path: &Path) -> Option<SystemTime> {
+    fs::read_dir(path.join("bin"))
+        .ok()
+        .and_then(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.metadata().ok()?.accessed().ok())
+                .max()
+        })
+        .or_else(|| fs::metadata(path).ok()?.modified().ok())
+}
+
+/// Reads the non-empty, non-comment lines of a file.
+fn read_lines(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the quoted value of a `key = "value"` TOML line.
+fn toml_string_value(line: &str) -> Option<String> {
+    let (_, value) = line.split_once('=')?;
+    Some(value.trim().trim_matches('"').trim_matches('\'').to_string())
+}
+
+/// Collects the default versions configured for every manager.
+fn default_versions() -> Vec<Requested> {
+    let mut requested = Vec::new();
+    let mut push = |manager, candidate: &str, version: String, reason: &str| {
+        requested.push(Requested {
+            manager,
+            candidate: candidate.to_string(),
+            version,
+            reason: reason.to_string(),
+        });
+    };
+
+    // rustup: `default_toolchain` and directory overrides in `settings.toml`.
+    let mut in_overrides = false;
+    for line in read_lines(&manager_root("RUSTUP_HOME", ".rustup").join("settings.toml")) {
+        if line.starts_with('[') {
+            in_overrides = line == "[overrides]";
+        } else if line.starts_with("default_toolchain") || in_overrides {
+            let reason = if in_overrides { "rustup override" } else { "default" };
+            if let Some(value) = toml_string_value(&line) {
+                push(ToolchainManager::Rustup, "", value, reason);
+            }
+        }
+    }
+    for line in read_lines(&manager_root("NVM_DIR", ".nvm").join("alias/default")) {
+        push(ToolchainManager::Nvm, "", line, "default");
+    }
+    for line in read_lines(&manager_root("PYENV_ROOT", ".pyenv").join("version")) {
+        push(ToolchainManager::Pyenv, "", line, "default");
+    }
+    for line in read_lines(&manager_root("RBENV_ROOT", ".rbenv").join("version")) {
+        push(ToolchainManager::Rbenv, "", line, "default");
+    }
+    // SDKMAN!: the `current` symlink of every candidate.
+    let candidates = manager_root("SDKMAN_DIR", ".sdkman").join("candidates");
+    if let Ok(entries) = fs::read_dir(&candidates) {
+        for entry in entries.flatten() {
+            if let Ok(target) = fs::read_link(entry.path().join("current"))
+                && let Some(version) = target.file_name()
+            {
+                let candidate = entry.file_name().to_string_lossy().to_string();
+                push(ToolchainManager::Sdkman, &candidate, version.to_string_lossy().to_string(), "default");
+            }
+        }
+    }
+    requested
+}
+
+/// Returns the project roots scanned for pin files: `OSX_PROJECT_ROOTS`, or common project folders.
+pub fn project_roots() -> Vec<PathBuf> {
+    let roots = env_list(PROJECT_ROOTS_ENV);
+    if !roots.is_empty() {
+        return roots.into_iter().map(PathBuf::from).collect();
+    }
+    let home = env::var("HOME").unwrap_or_default();
+    DEFAULT_PROJECT_ROOTS
+        .iter()
+        .map(|dir| PathBuf::from(format!("{}/{}", home, dir)))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Collects the versions pinned by pin files found under `roots`.
+fn pinned_versions(roots: &[PathBuf]) -> Vec<Requested> {
+    let mut requested = Vec::new();
+    for root in roots {
+        let walker = WalkDir::new(root)
+            .max_depth(PROJECT_SCAN_DEPTH)
+            .into_iter()
+            .filter_entry(|e| {
+                !(e.file_type().is_dir()
+                    && e.file_name().to_str().is_some_and(|n| SKIPPED_PROJECT_DIRS.contains(&n)))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file());
+
+        for entry in walker {
+            let path = entry.path();
+            let reason = format!("pinned by {}", path.display());
+            let mut push = |manager, candidate: &str, version: String| {
+                requested.push(Requested {
+                    manager,
+                    candidate: candidate.to_string(),
+                    version,
+                    reason: reason.clone(),
+                });
+            };
+            match entry.file_name().to_str() {
+                Some("rust-toolchain.toml") | Some("rust-toolchain") => {
+                    let lines = read_lines(path);
+                    // `rust-toolchain` may also be a legacy file holding only the channel.
+                    let channel = lines
+                        .iter()
+                        .find(|line| line.starts_with("channel"))
+                        .and_then(|line| toml_string_value(line))
+                        .or_else(|| lines.first().filter(|line| !line.contains('=') && !line.starts_with('[')).cloned());
+                    if let Some(channel) = channel {
+                        push(ToolchainManager::Rustup, "", channel);
+                    }
+                }
+                Some(".nvmrc") => read_lines(path).into_iter().for_each(|v| push(ToolchainManager::Nvm, "", v)),
+                Some(".python-version") => {
+                    // Several versions may be listed, one per line or separated by spaces.
+                    read_lines(path)
+                        .iter()
+                        .flat_map(|line| line.split_whitespace())
+                        .for_each(|v| push(ToolchainManager::Pyenv, "", v.to_string()));
+                }
+                Some(".ruby-version") => read_lines(path).into_iter().for_each(|v| push(ToolchainManager::Rbenv, "", v)),
+                Some(".sdkmanrc") => {
+                    for line in read_lines(path) {
+                        if let Some((candidate, version)) = line.split_once('=') {
+                            push(ToolchainManager::Sdkman, candidate.trim(), version.trim().to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    requested
+}
+
+/// Resolves a requested version through the aliases of its manager, so it names an installed version
+/// (see `resolve_alias_in`), with the nvm and pyenv folders of the environment.
+pub fn resolve_alias(manager: ToolchainManager, version: &str) -> String {
+    let root = match manager {
+        ToolchainManager::Nvm => manager_root("NVM_DIR", ".nvm"),
+        ToolchainManager::Pyenv => manager_root("PYENV_ROOT", ".pyenv"),
+        _ => return version.to_string(),
+    };
+    resolve_alias_in(manager, version, &root)
+}
+
+/// Resolves a requested version through the aliases of its manager, whose root folder is `root`
+/// (`$NVM_DIR`, `$PYENV_ROOT`), so it names an installed version:
+///
+/// * nvm aliases are read from `$NVM_DIR/alias` (`default`, `lts/iron`, user aliases), following chained
+///   aliases: `lts/iron` resolves to the Node.js version nvm installed for that LTS line.
+/// * pyenv virtualenvs (`versions/<venv>` symlinks, or `<version>/envs/<venv>`) resolve to the Python
+///   version they were created from, which they depend on.
+///
+/// Other requests are returned as they are.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::toolchains::{ToolchainManager, resolve_alias_in};
+/// use std::{env, fs, os::unix::fs::symlink};
+///
+/// let root = env::temp_dir().join(format!("osx-doctest-toolchain-aliases-{}", std::process::id()));
+/// let _ = fs::remove_dir_all(&root);
+/// fs::create_dir_all(root.join("nvm/alias/lts")).unwrap();
+/// fs::write(root.join("nvm/alias/lts/iron"), "v20.11.0\n").unwrap();
+/// fs::write(root.join("nvm/alias/default"), "lts/iron\n").unwrap();
+/// fs::create_dir_all(root.join("pyenv/versions/3.11.4/envs/api")).unwrap();
+/// symlink(root.join("pyenv/versions/3.11.4/envs/api"), root.join("pyenv/versions/api")).unwrap();
+/// let (nvm, pyenv) = (root.join("nvm"), root.join("pyenv"));
+///
+/// assert_eq!(resolve_alias_in(ToolchainManager::Nvm, "lts/iron", &nvm), "v20.11.0");
+/// assert_eq!(resolve_alias_in(ToolchainManager::Nvm, "default", &nvm), "v20.11.0");
+/// assert_eq!(resolve_alias_in(ToolchainManager::Nvm, "18", &nvm), "18");
+/// assert_eq!(resolve_alias_in(ToolchainManager::Pyenv, "api", &pyenv), "3.11.4");
+/// assert_eq!(resolve_alias_in(ToolchainManager::Pyenv, "3.11.4/envs/api", &pyenv), "3.11.4");
+/// assert_eq!(resolve_alias_in(ToolchainManager::Pyenv, "3.12.1", &pyenv), "3.12.1");
+/// ```
+pub fn resolve_alias_in(manager: ToolchainManager, version: &str, root: &Path) -> String {
+    match manager {
+        ToolchainManager::Nvm => {
+            let alias_dir = root.join("alias");
+            let mut version = version.to_string();
+            // Aliases may chain (`default` -> `lts/iron` -> `v20.11.0`); a few hops are enough.
+            for _ in 0..4 {
+                let Some(target) = read_lines(&alias_dir.join(&version)).into_iter().next() else {
+                    break;
+                };
+                version = target;
+            }
+            version
+        }
+        ToolchainManager::Pyenv => {
+            if let Some((base, _)) = version.split_once("/envs/") {
+                return base.to_string();
+            }
+            let versions = root.join("versions");
+            let Ok(target) = fs::read_link(versions.join(version)) else {
+                return version.to_string();
+            };
+            // The virtualenv lives in `<versions>/<base version>/envs/<name>`.
+            let target = versions.join(target);
+            let components: Vec<String> = target
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            components
+                .iter()
+                .position(|c| c == "envs")
+                .filter(|&i| i > 0)
+                .map(|i| components[i - 1].clone())
+                .unwrap_or_else(|| version.to_string())
+        }
+        _ => version.to_string(),
+    }
+}
+
+/// Inventories the installed toolchains, marking the default and pinned versions as kept.
+///
+/// Requests are first resolved through the managers' aliases (see `resolve_alias`). nvm aliases
+/// that cannot be resolved offline (`node`, `stable`, ...) keep the newest installed Node.js version.
+pub fn inventory_toolchains(project_roots: &[PathBuf]) -> Vec<Toolchain> {
+    let mut toolchains = installed_toolchains();
+    let mut requested = default_versions();
+    requested.extend(pinned_versions(project_roots));
+
+    for request in &mut requested {
+        let resolved = resolve_alias(request.manager, &request.version);
+        if resolved != request.version {
+            log_debug!("Resolved {} '{}' to {}", request.manager.name(), request.version, resolved);
+            request.reason = format!("{} ({})", request.reason, request.version);
+            request.version = resolved;
+        }
+    }
+
+    for request in &requested {
+        let mut matched = false;
+        for toolchain in toolchains.iter_mut().filter(|t| t.manager == request.manager) {
+            let version = match request.manager {
+                ToolchainManager::Sdkman => match toolchain.version.split_once('/') {
+                    Some((candidate, version)) if candidate == request.candidate => version,
+                    _ => continue,
+                },
+                _ => toolchain.version.as_str(),
+            };
+            if version_matches(version, &request.version) {
+                matched = true;
+                toolchain.keep_reason.get_or_insert_with(|| request.reason.clone());
+            }
+        }
+        let is_alias = !request.version.trim_start_matches('v').starts_with(|c: char| c.is_ascii_digit());
+        if !matched && is_alias && request.manager == ToolchainManager::Nvm {
+            log_debug!("Keeping the newest Node.js version for unresolved nvm alias '{}'", request.version);
+            if let Some(newest) = toolchains
+                .iter_mut()
+                .filter(|t| t.manager == ToolchainManager::Nvm)
+                .max_by_key(|t| version_key(&t.version))
+            {
+                newest.keep_reason.get_or_insert_with(|| format!("{} ({})", request.reason, request.version));
+            }
+        }
+    }
+
+    toolchains.sort_by(|a, b| {
+        (a.manager.name(), version_key(&b.version)).cmp(&(b.manager.name(), version_key(&a.version)))
+    });
+    toolchains
+}
+
+/// Runs the `toolchains` subcommand.
+///
+/// Lists every installed toolchain version with its size, last use and whether it is kept.
+/// With `prune`, unused versions are removed through `remove_path` (simulated in `dry_run`).
+pub fn run_toolchains(prune: bool, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let roots = project_roots();
+    for root in &roots {
+        log_info!("🔍 Scanning for pinned versions: {}", root.display().to_string().blue());
+    }
+    let toolchains = inventory_toolchains(&roots);
+    if toolchains.is_empty() {
+        log_info!("{}", "No toolchain versions found.".bright_white());
+        return Ok(());
+    }
+
+    let mut entries = Vec::new();
+    let mut failures = 0;
+    let mut unused_size = 0;
+    for toolchain in &toolchains {
+        let size = calculate_dir_size(&toolchain.path).unwrap_or(0);
+        let status = match &toolchain.keep_reason {
+            Some(reason) => format!("Keep: {}", reason),
+            None if !prune => {
+                unused_size += size;
+                "Unused".to_string()
+            }
+            None => match remove_path(&toolchain.path, dry_run) {
+                Ok(_) => {
+                    unused_size += size;
+                    if dry_run { "Would remove" } else { "Removed" }.to_string()
+                }
+                Err(e) => {
+                    log_error!("❌ Failed to remove {}: {}", toolchain.path.display(), e);
+                    failures += 1;
+                    format!("Failed: {}", e)
+                }
+            },
+        };
+        entries.push(ToolchainEntry {
+            manager: toolchain.manager.name().to_string(),
+            version: toolchain.version.clone(),
+            size: format_bytes(size),
+            last_used: format_age(toolchain.last_used),
+            status,
+        });
+    }
+
+    println!("\n{}\n", "🧰 Installed Toolchains".bold().underline().purple());
+    println!("{}", Table::new(&entries).with(Style::modern()));
+    eprintln!();
+    match prune {
+        false => log_info!(
+            "🧠 Used by unused versions: {} (use --prune to remove them)",
+            format_bytes(unused_size).bright_green().bold()
+        ),
+        true if dry_run => log_info!("🧠 Estimated space to free: {}", format_bytes(unused_size).bright_green().bold()),
+        true => log_info!("✔ Total space freed: {}", format_bytes(unused_size).bright_green().bold()),
+    }
+    if failures > 0 {
+        return Err(format!("{} toolchain(s) could not be removed", failures).into());
+    }
+    Ok(())
+}
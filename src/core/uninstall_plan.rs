@@ -0,0 +1,365 @@
+// This file builds the plan of an uninstall: every path that will be removed, with its category,
+// size and whether removing it needs root. The plan is shown (as a table or JSON) before anything
+// is deleted, and then executed: launchd jobs are unloaded, then the paths are removed.
+
+use crate::core::cleaners::{FailedEntry, format_bytes};
+use crate::core::launchd::{LaunchJob, bootout};
+use crate::utils::command::CommandRunner;
+use crate::utils::filesystem::{calculate_dir_size, invoking_uid, is_writable, remove_path};
+use crate::{log_debug, log_info};
+use colored::Colorize;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use tabled::{Table, Tabled, settings::Style};
+
+/// What a path of an uninstall plan holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PathCategory {
+    Bundle,
+    Binary,
+    ApplicationSupport,
+    Preferences,
+    Container,
+    Cache,
+    SavedState,
+    Logs,
+    LaunchAgent,
+    PrivilegedHelper,
+    Receipt,
+    Other,
+}
+
+impl PathCategory {
+    /// Classifies `path` by its location.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use osx::core::uninstall_plan::PathCategory;
+    /// use std::path::Path;
+    ///
+    /// let category = |path| PathCategory::of(Path::new(path));
+    /// assert_eq!(category("/Applications/Slack.app"), PathCategory::Bundle);
+    /// assert_eq!(category("/Users/jane/Library/Preferences/com.tinyspeck.slackmacgap.plist"), PathCategory::Preferences);
+    /// assert_eq!(category("/Users/jane/Library/Containers/com.tinyspeck.slackmacgap"), PathCategory::Container);
+    /// assert_eq!(category("/Users/jane/Library/HTTPStorages/com.tinyspeck.slackmacgap"), PathCategory::Cache);
+    /// assert_eq!(category("/Users/jane/Library/Application Support/CrashReporter/Slack_0A1B.plist"), PathCategory::Logs);
+    /// assert_eq!(category("/Users/jane/Library/Application Support/Slack"), PathCategory::ApplicationSupport);
+    /// assert_eq!(category("/Library/LaunchDaemons/com.docker.vmnetd.plist"), PathCategory::LaunchAgent);
+    /// assert_eq!(category("/Library/PrivilegedHelperTools/com.docker.vmnetd"), PathCategory::PrivilegedHelper);
+    /// assert_eq!(category("/var/db/receipts/com.docker.pkg.bom"), PathCategory::Receipt);
+    /// assert_eq!(category("/opt/homebrew/Cellar/wget"), PathCategory::Binary);
+    /// ```
+    pub fn of(path: &Path) -> Self {
+        let text = path.to_string_lossy();
+        let within = |dirs: &[&str]| dirs.iter().any(|dir| text.contains(dir));
+        if path.extension().is_some_and(|ext| ext == "app") {
+            PathCategory::Bundle
+        } else if within(&["/LaunchAgents/", "/LaunchDaemons/"]) {
+            PathCategory::LaunchAgent
+        } else if within(&["/PrivilegedHelperTools/"]) {
+            PathCategory::PrivilegedHelper
+        } else if text.starts_with("/var/db/receipts/") || text.starts_with("/Library/Receipts/") {
+            PathCategory::Receipt
+        } else if within(&["/Library/Preferences/"]) {
+            PathCategory::Preferences
+        } else if within(&["/Library/Containers/", "/Library/Group Containers/", "/Library/Application Scripts/"]) {
+            PathCategory::Container
+        } else if within(&["/Library/Caches/", "/Library/HTTPStorages/", "/Library/WebKit/", "/Library/Cookies/"]) {
+            PathCategory::Cache
+        } else if within(&["/Library/Saved Application State/"]) {
+            PathCategory::SavedState
+        } else if within(&["/Library/Logs/", "/CrashReporter/"]) {
+            PathCategory::Logs
+        } else if within(&["/Application Support/"]) {
+            PathCategory::ApplicationSupport
+        } else if within(&["/bin/", "/Cellar/", "/Caskroom/", "/lib/", "/Frameworks/"]) {
+            PathCategory::Binary
+        } else {
+            PathCategory::Other
+        }
+    }
+}
+
+impl fmt::Display for PathCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PathCategory::Bundle => "App bundle",
+            PathCategory::Binary => "Binary",
+            PathCategory::ApplicationSupport => "Application support",
+            PathCategory::Preferences => "Preferences",
+            PathCategory::Container => "Container",
+            PathCategory::Cache => "Cache",
+            PathCategory::SavedState => "Saved state",
+            PathCategory::Logs => "Logs",
+            PathCategory::LaunchAgent => "Launch agent",
+            PathCategory::PrivilegedHelper => "Privileged helper",
+            PathCategory::Receipt => "Receipt",
+            PathCategory::Other => "Other",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A path removed by an uninstall.
+#[derive(Clone, Debug, Serialize)]
+pub struct PlanEntry {
+    pub path: PathBuf,
+    pub category: PathCategory,
+    /// Size in bytes.
+    pub size: u64,
+    /// Whether removing the path needs root (its parent folder isn't writable by the current user).
+    pub requires_root: bool,
+}
+
+/// Everything an uninstall will remove.
+#[derive(Clone, Debug, Serialize)]
+pub struct UninstallPlan {
+    /// Name of the app or tool being uninstalled.
+    pub name: String,
+    pub entries: Vec<PlanEntry>,
+    /// Sum of the sizes of the entries, in bytes.
+    pub total_size: u64,
+    /// Folders created by installer packages, removed after the entries if they end up empty.
+    pub empty_dirs: Vec<PathBuf>,
+}
+
+/// Represents a row of the uninstall plan table.
+#[derive(Tabled)]
+struct PlanRow {
+    #[tabled(rename = "Category")]
+    category: String,
+    #[tabled(rename = "Path")]
+    path: String,
+    #[tabled(rename = "Size")]
+    size: String,
+    #[tabled(rename = "Root")]
+    requires_root: String,
+}
+
+impl UninstallPlan {
+    /// Builds the plan for `name` from candidate paths: paths that don't exist are left out, as are
+    /// paths inside another candidate (removed with it), and entries are sorted by category, then path.
+    pub fn new(name: &str, paths: Vec<PathBuf>) -> Self {
+        let mut paths: Vec<PathBuf> = paths.into_iter().filter(|path| path.symlink_metadata().is_ok()).collect();
+        // Sorted by components, a folder comes right before everything it contains.
+        paths.sort();
+        let mut outermost: Vec<PathBuf> = Vec::new();
+        for path in paths {
+            if !outermost.last().is_some_and(|parent| path.starts_with(parent)) {
+                outermost.push(path);
+            }
+        }
+
+        let mut entries: Vec<PlanEntry> = outermost
+            .into_par_iter()
+            .map(|path| PlanEntry {
+                category: PathCategory::of(&path),
+                size: calculate_dir_size(&path).unwrap_or(0),
+                requires_root: !path.parent().is_some_and(is_writable),
+                path,
+            })
+            .collect();
+        entries.sort_by(|a, b| (a.category, &a.path).cmp(&(b.category, &b.path)));
+        let total_size = entries.iter().map(|e| e.size).sum();
+        UninstallPlan {
+            name: name.to_string(),
+            entries,
+            total_size,
+            empty_dirs: Vec::new(),
+        }
+    }
+
+    /// Adds folders to remove after the entries if they end up empty (deepest first).
+    pub fn with_empty_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.empty_dirs = dirs.into_iter().filter(|dir| dir.is_dir()).collect();
+        self.empty_dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        self.empty_dirs.dedup();
+        self
+    }
+
+    /// Returns the plan without the entries at `paths`, e.g. those a package manager removes itself.
+    pub fn without(&self, paths: &[&Path]) -> UninstallPlan {
+        let entries: Vec<PlanEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| !paths.contains(&entry.path.as_path()))
+            .cloned()
+            .collect();
+        UninstallPlan {
+            name: self.name.clone(),
+            total_size: entries.iter().map(|e| e.size).sum(),
+            entries,
+            empty_dirs: self.empty_dirs.clone(),
+        }
+    }
+
+    /// Returns `true` if nothing was found to remove.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Prints the plan as a table, followed by the total footprint.
+    pub fn print(&self, dry_run: bool) {
+        let mut rows: Vec<PlanRow> = self
+            .entries
+            .iter()
+            .map(|entry| PlanRow {
+                category: entry.category.to_string(),
+                path: entry.path.display().to_string(),
+                size: format_bytes(entry.size),
+                requires_root: if entry.requires_root { "yes" } else { "" }.to_string(),
+            })
+            .collect();
+        rows.push(PlanRow {
+            category: "".to_string(),
+            path: "Total".to_string(),
+            size: format_bytes(self.total_size),
+            requires_root: "".to_string(),
+        });
+
+        let title = if dry_run {
+            format!("🧽 Uninstall Plan for '{}' (Dry Run)", self.name)
+        } else {
+            format!("🧽 Uninstall Plan for '{}'", self.name)
+        };
+        println!("\n{}\n", title.bold().underline().purple());
+        println!("{}", Table::new(&rows).with(Style::modern()));
+        eprintln!();
+        log_info!(
+            "🧠 Total footprint: {} in {} path(s)",
+            format_bytes(self.total_size).bright_green().bold(),
+            self.entries.len()
+        );
+        if !self.empty_dirs.is_empty() {
+            log_info!(
+                "📁 {} folder(s) created by installer packages are removed afterwards if they end up empty",
+                self.empty_dirs.len()
+            );
+        }
+        if self.entries.iter().any(|e| e.requires_root) {
+            log_info!("🔐 Some paths can only be removed when running with {}", "sudo".bright_yellow());
+        }
+    }
+
+    /// Removes every path of the plan (simulated in `dry_run`), then reports the failures in a table.
+    ///
+    /// LaunchAgents are booted out of the GUI session of the user who invoked the tool, the one behind
+    /// `sudo` if any (see `execute_in_session`).
+    pub fn execute(&self, runner: &dyn CommandRunner, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.execute_in_session(runner, invoking_uid(), dry_run)
+    }
+
+    /// Removes every path of the plan (simulated in `dry_run`), then reports the failures in a table.
+    ///
+    /// The launchd jobs of the plan's LaunchAgents and LaunchDaemons are booted out through `runner`
+    /// before their plists are removed, so they don't keep running until the next reboot; LaunchAgents
+    /// from the GUI session of the user `uid`. When a job can't be booted out (e.g. a LaunchDaemon without
+    /// root), its plist and privileged helpers are kept, so that the job isn't left loaded without them.
+    pub fn execute_in_session(
+        &self,
+        runner: &dyn CommandRunner,
+        uid: u32,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if dry_run {
+            return Ok(());
+        }
+        let mut failures: Vec<FailedEntry> = Vec::new();
+        let mut kept: HashSet<PathBuf> = HashSet::new();
+        for entry in self.entries.iter().filter(|entry| entry.category == PathCategory::LaunchAgent) {
+            let job = LaunchJob::read(&entry.path);
+            if let Err(e) = bootout(runner, &job, uid) {
+                failures.push(FailedEntry {
+                    path: entry.path.display().to_string(),
+                    error: format!("{}; kept with its helpers", e),
+                });
+                kept.insert(entry.path.clone());
+                kept.extend(job.privileged_helpers());
+            }
+        }
+        let removal_failures: Vec<FailedEntry> = self
+            .entries
+            .par_iter()
+            .filter(|entry| !kept.contains(&entry.path))
+            .filter_map(|entry| {
+                remove_path(&entry.path, dry_run).err().map(|e| FailedEntry {
+                    path: entry.path.display().to_string(),
+                    error: e.to_string(),
+                })
+            })
+            .collect();
+        failures.extend(removal_failures);
+
+        // Folders created by installer packages, deepest first so that their parents can become empty too.
+        for dir in &self.empty_dirs {
+            if fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none())
+                && let Err(e) = fs::remove_dir(dir)
+            {
+                log_debug!("Could not remove empty folder {}: {}", dir.display(), e);
+            }
+        }
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+        eprintln!();
+        println!("{}", "⚠️ Uninstall Failures".bold().underline().yellow());
+        println!("{}", Table::new(&failures).with(Style::modern()));
+        Err(format!("{} path(s) could not be removed", failures.len()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::command::testing::RecordingRunner;
+    use std::{env, process};
+
+    /// Writes a LaunchAgent plist for the job `label` in a fixture `Library/LaunchAgents`, unique to this process.
+    fn launch_agent(fixture: &str, label: &str) -> PathBuf {
+        let agent = env::temp_dir()
+            .join(format!("{}-{}", fixture, process::id()))
+            .join(format!("Library/LaunchAgents/{}.plist", label));
+        fs::create_dir_all(agent.parent().unwrap()).unwrap();
+        fs::write(
+            &agent,
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict><key>Label</key><string>{}</string></dict></plist>"#,
+                label
+            ),
+        )
+        .unwrap();
+        agent
+    }
+
+    #[test]
+    fn execute_boots_out_launch_agents_before_removing_them() {
+        let agent = launch_agent("osx-test-plan-bootout", "com.example.sync");
+
+        let plan = UninstallPlan::new("Example", vec![agent.clone()]);
+        let runner = RecordingRunner::succeeding();
+        plan.execute_in_session(&runner, 501, true).unwrap();
+        assert!(runner.commands().is_empty() && agent.exists());
+
+        plan.execute_in_session(&runner, 501, false).unwrap();
+        assert_eq!(runner.commands(), vec!["launchctl bootout gui/501/com.example.sync"]);
+        assert!(!agent.exists());
+    }
+
+    #[test]
+    fn execute_keeps_launch_agents_that_could_not_be_booted_out() {
+        let agent = launch_agent("osx-test-plan-bootout-failure", "com.example.helper");
+        let plan = UninstallPlan::new("Example", vec![agent.clone()]);
+        let runner = RecordingRunner::failing("Boot-out failed: 1: Operation not permitted");
+        assert!(plan.execute_in_session(&runner, 501, false).is_err());
+        assert!(agent.exists());
+    }
+}
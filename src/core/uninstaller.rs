@@ -1,12 +1,19 @@
-use crate::utils::filesystem::remove_path;
-// Imports the `remove_path` function, which is assumed to handle file/directory deletion.
-use rayon::prelude::*;
+use crate::core::installed_apps::{AppMatch, InstalledApp, app_dirs, installed_apps, read_app, resolve_app};
+use crate::core::homebrew::{HomebrewPackage, brew_uninstall, cask_of_app, find_homebrew_package};
+use crate::core::launchd::LaunchJob;
+use crate::core::processes::{ProcessTable, processes_in_plan, quit_timeout, stop_processes};
+use crate::core::package_managers::{ManagedTool, detect_managed_tool, find_on_path, uninstall_managed_tool};
+use crate::core::receipts::{is_apple_receipt, paths_of_other_receipts, receipt_removal};
+use crate::core::uninstall_plan::UninstallPlan;
+use crate::utils::command::CommandRunner;
+// Imports the plan listing what an uninstall removes, which also performs the deletion.
+use glob::glob;
 use crate::{log_debug, log_info, log_warn};
-// Imports traits from the `rayon` crate to enable parallel iteration over collections, improving performance for I/O bound tasks.
 use std::{
+    collections::HashSet,
     // Provides access to environment variables, like HOME.
     fs, // Provides file system primitives for interacting with files and directories.
-    path::PathBuf, // A mutable, owned path type for building and manipulating file paths.
+    path::{Path, PathBuf}, // Path types for building and manipulating file paths.
 };
 // Imports custom logging macros for different severity levels (debug, info, warn).
 use colored::Colorize;
@@ -22,104 +29,279 @@ pub trait Uninstaller {
     /// This name is used for logging and for constructing common file paths.
     fn name(&self) -> &str;
 
+    /// Returns the bundle identifier of the app, if known.
+    /// Launch agents and package receipts are only matched by this identifier: without one, none are removed.
+    fn bundle_id(&self) -> Option<&str> {
+        None
+    }
+
     /// Discovers and returns a list of file system paths associated with the specific app or tool.
     /// These paths represent files and directories that belong to the entity and should be removed during uninstallation.
     fn find_related_paths(&self) -> Vec<PathBuf>;
 
-    /// Executes the uninstallation process.
+    /// Builds the plan of the uninstall: every existing path to remove, with its category and size.
     ///
-    /// It first gathers all relevant paths by combining `find_related_paths` with
-    /// common macOS-specific locations for launch agents and package receipts.
+    /// The default plan is `standard_plan`.
+    fn plan(&self) -> UninstallPlan {
+        standard_plan(self)
+    }
+
+    /// Carries out `plan` (simulated in `dry_run`). By default, its paths are removed (see `UninstallPlan::execute`);
+    /// implementations whose files are owned by a package manager delegate to it instead.
+    fn execute(
+        &self,
+        plan: &UninstallPlan,
+        runner: &dyn CommandRunner,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        plan.execute(runner, dry_run)
+    }
+
+    /// Makes sure nothing of `plan` is running before it is carried out.
     ///
-    /// The deletion is performed in parallel using Rayon for efficiency.
+    /// Processes whose executable lives inside a bundle or binary of the plan are listed through `processes`.
+    /// With `force` they are terminated (see `stop_processes`); otherwise an error names them and the
+    /// uninstall is aborted.
+    fn quit_running(
+        &self,
+        plan: &UninstallPlan,
+        processes: &dyn ProcessTable,
+        force: bool,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let running = processes_in_plan(plan, &processes.list()?);
+        Ok(stop_processes(processes, self.name(), &running, force, dry_run, quit_timeout())?)
+    }
+
+    /// Executes the uninstallation process.
+    ///
+    /// The plan (see `plan`) is printed as a table with the total footprint. Running processes of the plan
+    /// are then handled by `quit_running`, and the plan carried out by `execute`: launchd jobs are unloaded
+    /// through `runner` and every path is removed, in parallel using Rayon.
     ///
     /// # Arguments
-    /// * `dry_run` - A boolean flag. If `true`, the uninstaller will only log which files *would* be deleted
+    /// * `runner` - Runs external commands (`launchctl`, `brew`); tests can pass a mock.
+    /// * `processes` - Lists and signals the running processes; tests can pass a mock.
+    /// * `force` - Terminates the running processes of the plan instead of aborting the uninstall.
+    /// * `dry_run` - A boolean flag. If `true`, the uninstaller will only show which files *would* be deleted
     ///               without actually performing any deletions. If `false`, actual deletion occurs.
     ///
     /// # Returns
-    /// A `Result` indicating success (`Ok(())`) or failure (`Err(Box<dyn std::error::Error>)`).
-    /// Errors are typically related to underlying file system operations.
-    fn uninstall(&self, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    /// A `Result` indicating success (`Ok(())`) or failure (`Err(Box<dyn std::error::Error>)`)
+    /// when some paths could not be removed; those are listed in a failure table.
+    fn uninstall(
+        &self,
+        runner: &dyn CommandRunner,
+        processes: &dyn ProcessTable,
+        force: bool,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // Log the initiation of the uninstall process for clarity.
         log_debug!("Starting uninstall for '{}'", self.name().bright_white());
 
-        // TODO: Advanced Improvement: Before proceeding with file deletion,
-        // it would be beneficial to check if the target application is currently running.
-        // If it is, the uninstaller could:
-        // 1. Prompt the user to manually quit the application.
-        // 2. Attempt to programmatically quit the application (requires elevated permissions and careful handling).
-        // This prevents "Resource busy" errors and ensures a cleaner uninstall.
-
-        // Collect all paths identified by the specific uninstaller implementation.
-        let mut paths = self.find_related_paths();
-        // Extend the list with paths to launch agents/daemons that might be associated with the app.
-        paths.extend(find_launch_agents_for_app(self.name()));
-        // Extend the list with paths to package installation receipts.
-        paths.extend(find_pkg_receipts(self.name()));
-
-        // Use Rayon's parallel iterator to process each path concurrently.
-        // This can significantly speed up the operation, especially for many files.
-        paths.par_iter().for_each(|path| {
-            // Log the current path being considered for removal.
-            log_debug!("Processing path: {}", path.display().to_string().bright_white());
-
-            // Check if the path actually exists on the file system.
-            if !path.exists() {
-                // If the path does not exist, log a warning and skip to the next path.
-                log_warn!("{}: {}", "Not found".bright_yellow(), path.display().to_string().bright_white());
-                return; // Skips the rest of the current iteration for this path.
-            }
-
-            // Differentiate between a dry run and an actual deletion.
-            if dry_run {
-                // In dry-run mode, simply log what *would* be deleted without modifying the file system.
-                log_info!("{}: {}", "Would delete".bright_green(), path.display().to_string().bright_white());
-            } else {
-                // In actual uninstall mode, attempt to delete the path.
-                log_info!("{}: {}", "Deleting".bright_green(), path.display().to_string().bright_white());
-                // Call the `remove_path` utility function.
-                if let Err(e) = remove_path(path, dry_run) {
-                    // If deletion fails, log a warning with the specific path and the error message.
-                    log_warn!("{} {}: {}", "Failed to delete".bright_yellow(),
-                        path.display().to_string().bright_white(), e.to_string().bright_white());
-                    // TODO: Advanced Improvement: Instead of just logging, these failed entries could be collected
-                    // into a `Arc<Mutex<Vec<FailedEntry>>>` (similar to `cleaner.rs`) and reported in a final summary table
-                    // to provide a more comprehensive overview of the uninstall failures to the user.
-                }
-            }
-        });
+        let plan = self.plan();
+        if plan.is_empty() {
+            log_info!("Nothing found to remove for '{}'", self.name().bright_white());
+            return Ok(());
+        }
+        plan.print(dry_run);
+        // A running app would be left half-removed and recreate its caches: quit it first, or abort.
+        self.quit_running(&plan, processes, force, dry_run)?;
+        let result = self.execute(&plan, runner, dry_run);
 
         // Log the completion of the uninstallation process for the specific app.
         log_debug!("Completed uninstall for '{}'", self.name().to_string().bright_white());
-        // Return `Ok(())` if the uninstall process completed without critical errors,
-        // even if some individual file deletions failed (which are logged as warnings).
-        Ok(())
+        result
     }
 }
 
-/// Discovers and returns a list of `.plist` files that serve as Launch Agents or Launch Daemons
-/// and appear to be related to the given application name.
-/// These files are used by macOS to automatically launch applications or scripts at boot or login.
+/// Builds the standard plan of an uninstall: every existing path to remove, with its category and size.
 ///
-/// This function performs a case-insensitive substring search on the filename.
-fn find_launch_agents_for_app(app_name: &str) -> Vec<PathBuf> {
-    let mut plist_paths = Vec::new(); // Initialize an empty vector to store the found .plist paths.
+/// It gathers all relevant paths by combining `find_related_paths` with common macOS-specific
+/// locations for launch agents and package receipts (with the files the packages installed),
+/// and expands wildcard candidates. Launch agents and receipts are matched by the bundle identifier.
+pub fn standard_plan<U: Uninstaller + ?Sized>(uninstaller: &U) -> UninstallPlan {
+    // Collect all paths identified by the specific uninstaller implementation.
+    let mut paths = uninstaller.find_related_paths();
+    // Extend the list with paths to launch agents/daemons that might be associated with the app,
+    // and the privileged helper tools their jobs run.
+    let launch_agents = find_launch_agents_for_app(uninstaller.bundle_id());
+    paths.extend(launch_agents.iter().flat_map(|plist| LaunchJob::read(plist).privileged_helpers()));
+    paths.extend(launch_agents);
+    // Expand wildcard candidates (e.g. `~/Library/Containers/<id>.*`) into the paths that exist.
+    let mut paths = expand_path_patterns(paths);
+    // Extend the list with paths to package installation receipts, and the files their packages
+    // installed according to the receipts' BOM. Removing the receipt files forgets the packages.
+    // These paths are real file names, added after the expansion so that `*`, `?` or `[` in them
+    // are never taken as wildcards.
+    let receipt_dirs: Vec<&Path> = RECEIPT_DIRS.iter().map(Path::new).collect();
+    let receipts = find_pkg_receipts(&receipt_dirs, uninstaller.bundle_id());
+    // Only the package named exactly after the bundle identifier is the app's own: packages of its helpers
+    // or plug-ins (`<id>.*`) may have installed into other apps, so their BOM isn't followed.
+    let plists: Vec<PathBuf> = receipts
+        .iter()
+        .filter(|r| r.extension().is_some_and(|ext| ext == "plist"))
+        .filter(|r| {
+            r.file_stem()
+                .zip(uninstaller.bundle_id())
+                .is_some_and(|(stem, id)| stem.to_string_lossy().eq_ignore_ascii_case(id))
+        })
+        .cloned()
+        .collect();
+    let mut empty_dirs = Vec::new();
+    if !plists.is_empty() {
+        // Files also installed by other packages (a shared framework, a `/usr/local/bin` link) stay.
+        let other_packages = paths_of_other_receipts(&receipt_dirs, &plists);
+        for receipt in &plists {
+            let removal = receipt_removal(receipt, &other_packages);
+            paths.extend(removal.paths);
+            empty_dirs.extend(removal.empty_dirs);
+        }
+    }
+    paths.extend(receipts);
+    UninstallPlan::new(uninstaller.name(), paths).with_empty_dirs(empty_dirs)
+}
 
+/// Expands the candidate paths containing wildcards (`*` or `?`) into the existing paths they match,
+/// and removes duplicates. Paths without wildcards are kept as they are, whether they exist or not.
+///
+/// Brackets are matched literally, so app names such as `Foo [Beta]` don't need escaping.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::uninstaller::{MacApp, Uninstaller, expand_path_patterns};
+/// use std::{env, fs, path::PathBuf};
+///
+/// // A fixture home with an installed app and the data it left in `~/Library`.
+/// let home = env::temp_dir().join(format!("osx-doctest-expand-home-{}", std::process::id()));
+/// let bundle = home.join("Applications/Notes Pro.app");
+/// fs::create_dir_all(bundle.join("Contents")).unwrap();
+/// fs::write(
+///     bundle.join("Contents/Info.plist"),
+///     r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <plist version="1.0"><dict>
+///     <key>CFBundleIdentifier</key><string>com.acme.notespro</string>
+/// </dict></plist>"#,
+/// )
+/// .unwrap();
+/// let library = home.join("Library");
+/// let expected = [
+///     library.join("Containers/com.acme.notespro.ShareExtension"),
+///     library.join("Group Containers/ABCDE12345.com.acme.notespro"),
+///     library.join("Application Support/CrashReporter/Notes Pro_0A1B2C3D.plist"),
+///     library.join("Preferences/ByHost/com.acme.notespro.0A1B2C3D-4E5F.plist"),
+/// ];
+/// // Another vendor's container whose name contains the app name.
+/// let unrelated = library.join("Group Containers/XYZ9876543.com.other.Notes Pro.sync");
+/// for path in expected.iter().chain([&unrelated]) {
+///     fs::create_dir_all(path.parent().unwrap()).unwrap();
+///     fs::write(path, "").unwrap();
+/// }
+///
+/// let paths = expand_path_patterns(MacApp::from_bundle(&bundle).with_home(&home).find_related_paths());
+/// for path in &expected {
+///     assert!(paths.contains(path), "{} not found", path.display());
+/// }
+/// assert!(!paths.contains(&unrelated));
+/// assert!(paths.contains(&bundle));
+/// assert!(!paths.iter().any(|p| p.to_string_lossy().contains('*')));
+///
+/// // Literal paths are kept, wildcard paths without matches are dropped.
+/// let paths = expand_path_patterns(vec![
+///     PathBuf::from("/nonexistent/Foo [Beta]"),
+///     PathBuf::from("/nonexistent/Foo [Beta].*"),
+/// ]);
+/// assert_eq!(paths, vec![PathBuf::from("/nonexistent/Foo [Beta]")]);
+/// ```
+pub fn expand_path_patterns(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut expanded = Vec::new();
+    for path in paths {
+        let candidate = path.to_string_lossy();
+        if !candidate.contains(['*', '?']) {
+            expanded.push(path);
+            continue;
+        }
+        // Brackets are never used as character classes in candidates: match them literally.
+        let pattern: String = candidate
+            .chars()
+            .map(|c| match c {
+                '[' => "[[]".to_string(),
+                ']' => "[]]".to_string(),
+                c => c.to_string(),
+            })
+            .collect();
+        match glob(&pattern) {
+            Ok(matches) => expanded.extend(matches.flatten()),
+            Err(e) => log_debug!("Invalid path pattern {}: {}", candidate, e),
+        }
+    }
+    let mut seen = HashSet::new();
+    expanded.retain(|p| seen.insert(p.clone()));
+    expanded
+}
+
+/// Directories where command-line tools are commonly installed or symlinked.
+pub const BIN_DIRS: &[&str] = &[
+    "/usr/local/bin", // Common for user-installed binaries.
+    "/usr/bin", // System binaries (less common for uninstallation).
+    "/opt/homebrew/bin", // Homebrew's default binary symlink path.
+];
+
+/// Directories where macOS stores package installation receipts.
+const RECEIPT_DIRS: &[&str] = &["/var/db/receipts", "/Library/Receipts"];
+
+/// Returns the standard directories where macOS stores Launch Agents and Launch Daemons.
+pub fn launch_agent_dirs() -> Vec<PathBuf> {
     // Get the current user's home directory to construct user-specific LaunchAgents path.
     let home = std::env::var("HOME").unwrap_or_default();
-    let user_launch_agents = format!("{}/Library/LaunchAgents", home);
-
-    // Define the standard directories where macOS stores Launch Agents and Launch Daemons.
-    // These are converted to `String` to be owned and avoid lifetime issues in the `for` loop.
-    let dirs = vec![
-        "/Library/LaunchAgents".to_string(), // System-wide Launch Agents.
-        "/Library/LaunchDaemons".to_string(), // System-wide Launch Daemons.
-        user_launch_agents, // User-specific Launch Agents.
-    ];
-
-    // Iterate through each of the defined directories.
-    for dir in dirs {
+    vec![
+        PathBuf::from("/Library/LaunchAgents"), // System-wide Launch Agents.
+        PathBuf::from("/Library/LaunchDaemons"), // System-wide Launch Daemons.
+        PathBuf::from(format!("{}/Library/LaunchAgents", home)), // User-specific Launch Agents.
+    ]
+}
+
+/// Returns `true` if the launch agent or receipt file `file_name` belongs to the app whose bundle
+/// identifier is `bundle_id`.
+///
+/// The file must be named after the identifier exactly (`<id>.plist`, `<id>.bom`), or after one of its
+/// helpers (`<id>.helper.plist`), ignoring case. Names are never matched: `google` would select every
+/// `com.google.*` job and receipt.
+///
+/// # Example
+///
+/// ```
+/// use osx::core::uninstaller::belongs_to_app;
+///
+/// let slack = "com.tinyspeck.slackmacgap";
+/// assert!(belongs_to_app("com.tinyspeck.slackmacgap.plist", slack));
+/// assert!(belongs_to_app("com.tinyspeck.slackmacgap.ShipIt.plist", slack));
+/// assert!(!belongs_to_app("com.tinyspeck.slackmacgapbeta.plist", slack));
+/// assert!(!belongs_to_app("com.slack.helper.plist", slack));
+/// ```
+pub fn belongs_to_app(file_name: &str, bundle_id: &str) -> bool {
+    let stem = file_name
+        .strip_suffix(".plist")
+        .or_else(|| file_name.strip_suffix(".bom"))
+        .unwrap_or(file_name)
+        .to_lowercase();
+    let id = bundle_id.to_lowercase();
+    stem == id || stem.starts_with(&format!("{}.", id))
+}
+
+/// Discovers and returns a list of `.plist` files that serve as Launch Agents or Launch Daemons
+/// and belong to the app with the bundle identifier `bundle_id` (see `belongs_to_app`).
+/// These files are used by macOS to automatically launch applications or scripts at boot or login.
+/// Without a bundle identifier, nothing is returned.
+fn find_launch_agents_for_app(bundle_id: Option<&str>) -> Vec<PathBuf> {
+    let mut plist_paths = Vec::new(); // Initialize an empty vector to store the found .plist paths.
+    let Some(bundle_id) = bundle_id else {
+        return plist_paths;
+    };
+
+    // Iterate through each of the standard Launch Agents/Daemons directories.
+    for dir in launch_agent_dirs() {
         // Attempt to read the contents of the directory.
         if let Ok(entries) = fs::read_dir(&dir) {
             // Iterate over each entry (file or subdirectory) in the directory.
@@ -128,10 +310,8 @@ fn find_launch_agents_for_app(app_name: &str) -> Vec<PathBuf> {
                 let path = entry.path(); // Get the full path of the current entry.
                 // Extract the filename from the path and convert it to a string if possible.
                 if let Some(fname) = path.file_name().and_then(|s| s.to_str()) {
-                    // Check if the filename (converted to lowercase) contains the app name (also lowercase)
-                    // AND if the filename ends with ".plist".
-                    // This is a heuristic: a more precise method would parse the plist content for a BundleIdentifier or Label key.
-                    if fname.to_lowercase().contains(&app_name.to_lowercase()) && fname.ends_with(".plist") {
+                    // Keep the `.plist` files named after the app's bundle identifier.
+                    if fname.ends_with(".plist") && belongs_to_app(fname, bundle_id) {
                         plist_paths.push(path); // If both conditions are met, add the path to our list.
                     }
                 }
@@ -143,16 +323,16 @@ fn find_launch_agents_for_app(app_name: &str) -> Vec<PathBuf> {
 }
 
 /// Discovers and returns a list of package installation receipt files (`.pkg` or related)
-/// that appear to be associated with the given application name.
+/// in `receipt_dirs` that belong to the app with the bundle identifier `bundle_id` (see `belongs_to_app`).
 /// These receipts track what files were installed by a macOS installer package.
-///
-/// This function performs a case-insensitive substring search on the filename.
-fn find_pkg_receipts(app_name: &str) -> Vec<PathBuf> {
+/// Without a bundle identifier, nothing is returned.
+fn find_pkg_receipts(receipt_dirs: &[&Path], bundle_id: Option<&str>) -> Vec<PathBuf> {
     let mut receipts = Vec::new(); // Initialize an empty vector to store the found receipt paths.
-    // Define the standard directories where macOS stores package installation receipts.
-    let receipt_dirs = vec!["/var/db/receipts", "/Library/Receipts"];
+    let Some(bundle_id) = bundle_id else {
+        return receipts;
+    };
 
-    // Iterate through each of the defined receipt directories.
+    // Iterate through each of the standard receipt directories.
     for dir in receipt_dirs {
         // Attempt to read the contents of the directory.
         if let Ok(entries) = fs::read_dir(dir) {
@@ -161,9 +341,9 @@ fn find_pkg_receipts(app_name: &str) -> Vec<PathBuf> {
                 let path = entry.path(); // Get the full path of the current entry.
                 // Extract the filename from the path and convert it to a string.
                 if let Some(fname) = path.file_name().and_then(|s| s.to_str()) {
-                    // Check if the filename (converted to lowercase) contains the app name (also lowercase).
-                    // Receipt names can be varied, so a substring match is a common approach.
-                    if fname.to_lowercase().contains(&app_name.to_lowercase()) {
+                    // Receipts are named after the package identifier (e.g. `com.docker.docker.plist`/`.bom`).
+                    // Apple's own packages are never uninstalled, whatever the name matches.
+                    if !is_apple_receipt(fname) && belongs_to_app(fname, bundle_id) {
                         receipts.push(path); // If the condition is met, add the path to our list.
                     }
                 }
@@ -174,20 +354,186 @@ fn find_pkg_receipts(app_name: &str) -> Vec<PathBuf> {
     receipts // Return the vector of discovered receipt paths.
 }
 
+/// Returns the home folder of the current user (`HOME`), empty if unset.
+fn home_dir() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_default())
+}
+
 /// Represents a standard macOS Graphical User Interface (GUI) application.
 /// This struct implements the `Uninstaller` trait to define how a typical `.app` bundle
 /// and its associated files should be uninstalled.
 pub struct MacApp {
     name: String, // Stores the exact name of the macOS application (e.g., "Google Chrome", "Safari").
+    // Identity read from the bundle's `Info.plist`, or `None` if the bundle could not be found.
+    bundle: Option<InstalledApp>,
+    // The Homebrew cask that installed the bundle, if any: the bundle is then removed with `brew uninstall --cask`.
+    cask: Option<HomebrewPackage>,
+    // Whether `brew autoremove` runs after `brew uninstall`, to remove dependencies nothing needs anymore.
+    autoremove: bool,
+    // Home folder of the user whose data is removed, `HOME` by default.
+    home: PathBuf,
 }
 
 impl MacApp {
     /// Creates a new `MacApp` uninstaller instance.
+    ///
+    /// The app bundle is looked up as `<name>.app` in the application directories (see `app_dirs`),
+    /// and its `Info.plist` is read to locate the data stored under its bundle identifier.
+    /// Apps of the system (under `/System`) are never looked up: their data belongs to macOS.
     /// # Arguments
     /// * `name` - The name of the application.
     pub fn new(name: &str) -> Self {
+        let bundle = app_dirs()
+            .into_iter()
+            .filter(|dir| !dir.starts_with("/System"))
+            .map(|dir| dir.join(format!("{}.app", name)))
+            .find(|path| path.is_dir())
+            .map(|path| read_app(&path));
+        if bundle.is_none() {
+            log_debug!("No bundle found for '{}', guessing related paths from its name", name);
+        }
         Self {
             name: name.to_string(), // Convert the string slice to an owned String.
+            cask: bundle.as_ref().and_then(|b| cask_of_app(&b.path)),
+            bundle,
+            autoremove: false,
+            home: home_dir(),
+        }
+    }
+
+    /// Creates a `MacApp` uninstaller instance for the app bundle at `path`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use osx::core::uninstaller::{MacApp, Uninstaller};
+    /// use std::{env, fs, path::{Path, PathBuf}};
+    ///
+    /// let bundle = env::temp_dir().join(format!("osx-doctest-macapp-{}/Slack.app", std::process::id()));
+    /// fs::create_dir_all(bundle.join("Contents")).unwrap();
+    /// fs::write(
+    ///     bundle.join("Contents/Info.plist"),
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <plist version="1.0"><dict>
+    ///     <key>CFBundleIdentifier</key><string>com.tinyspeck.slackmacgap</string>
+    ///     <key>CFBundleName</key><string>Slack</string>
+    ///     <key>CFBundleExecutable</key><string>Slack</string>
+    /// </dict></plist>"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// let paths = MacApp::from_bundle(&bundle).with_home(Path::new("/Users/jane")).find_related_paths();
+    /// assert!(paths.contains(&bundle));
+    /// for path in [
+    ///     "/Users/jane/Library/Preferences/com.tinyspeck.slackmacgap.plist",
+    ///     "/Users/jane/Library/Containers/com.tinyspeck.slackmacgap",
+    ///     "/Users/jane/Library/Caches/com.tinyspeck.slackmacgap",
+    ///     "/Users/jane/Library/Saved Application State/com.tinyspeck.slackmacgap.savedState",
+    ///     "/Users/jane/Library/HTTPStorages/com.tinyspeck.slackmacgap",
+    ///     "/Users/jane/Library/WebKit/com.tinyspeck.slackmacgap",
+    ///     "/Users/jane/Library/Application Support/Slack",
+    /// ] {
+    ///     assert!(paths.contains(&PathBuf::from(path)), "{} not found", path);
+    /// }
+    /// ```
+    ///
+    /// Bundles under `/System` are refused: the app is then identified by its name only, so the data
+    /// macOS stores under the bundle identifier (e.g. `com.apple.Notes`) is never targeted.
+    pub fn from_bundle(path: &Path) -> Self {
+        let bundle = read_app(path);
+        if path.starts_with("/System") {
+            log_warn!("{} is part of macOS, ignoring its bundle", path.display());
+            return Self {
+                name: bundle.name,
+                bundle: None,
+                cask: None,
+                autoremove: false,
+                home: home_dir(),
+            };
+        }
+        Self {
+            name: bundle.name.clone(),
+            bundle: Some(bundle),
+            cask: cask_of_app(path),
+            autoremove: false,
+            home: home_dir(),
+        }
+    }
+
+    /// Looks for the app's data in the home folder `home` instead of `HOME`.
+    pub fn with_home(mut self, home: &Path) -> Self {
+        self.home = home.to_path_buf();
+        self
+    }
+
+    /// Runs `brew autoremove` after uninstalling an app installed by a Homebrew cask when `autoremove` is set.
+    pub fn with_autoremove(mut self, autoremove: bool) -> Self {
+        self.autoremove = autoremove;
+        self
+    }
+}
+
+/// What the name given to `osx uninstall` designates (see `resolve_mac_app`).
+pub enum AppResolution {
+    /// An installed app.
+    Installed(MacApp),
+    /// No installed app: the `MacApp` only looks for leftovers named after the query, and the query
+    /// may name a CLI tool.
+    NotInstalled(MacApp),
+    /// The query is ambiguous, only matches approximately, or names an app of the system: nothing is uninstalled.
+    Unresolved,
+}
+
+/// Resolves the app the user means by `query` among the installed apps (see `resolve_app`).
+///
+/// Returns `AppResolution::Unresolved` after listing the candidates when the query is ambiguous or only
+/// matches approximately, and when it names an app of the system (under `/System`).
+pub fn resolve_mac_app(query: &str) -> AppResolution {
+    // Apps in the system directories can't be removed: never offer them.
+    let (system_apps, apps): (Vec<InstalledApp>, Vec<InstalledApp>) = installed_apps()
+        .into_iter()
+        .partition(|app| app.path.starts_with("/System"));
+    match resolve_app(query, &apps) {
+        AppMatch::Found(app) => {
+            if app.name != query {
+                log_info!(
+                    "Resolved '{}' to {} ({})",
+                    query,
+                    app.name.bright_green(),
+                    app.bundle_id.as_deref().unwrap_or("no bundle ID").white().dimmed()
+                );
+            }
+            AppResolution::Installed(MacApp::from_bundle(&app.path))
+        }
+        AppMatch::Ambiguous(candidates) => {
+            log_info!("'{}' does not name an installed app exactly. Did you mean:", query.bright_yellow());
+            for app in candidates {
+                log_info!(
+                    "  • {} ({}) – {}",
+                    app.name.bright_white(),
+                    app.bundle_id.as_deref().unwrap_or("no bundle ID"),
+                    app.path.display().to_string().white().dimmed()
+                );
+            }
+            log_info!(
+                "Run the command again with the app name or bundle ID, or with {} to uninstall the CLI tool '{}'.",
+                "--tool".bright_white(),
+                query
+            );
+            AppResolution::Unresolved
+        }
+        AppMatch::NotFound => {
+            // Looking for leftovers of a system app would target the data macOS keeps for it (e.g. notes).
+            if let AppMatch::Found(app) = resolve_app(query, &system_apps) {
+                log_info!(
+                    "'{}' is part of macOS ({}) and can't be uninstalled",
+                    query.bright_yellow(),
+                    app.path.display().to_string().white().dimmed()
+                );
+                return AppResolution::Unresolved;
+            }
+            log_debug!("No installed app matches '{}', looking for leftovers only", query);
+            AppResolution::NotInstalled(MacApp::new(query))
         }
     }
 }
@@ -199,44 +545,116 @@ impl Uninstaller for MacApp {
         &self.name // Return a reference to the stored application name.
     }
 
+    /// Returns the `CFBundleIdentifier` read from the bundle's `Info.plist`.
+    fn bundle_id(&self) -> Option<&str> {
+        self.bundle.as_ref().and_then(|b| b.bundle_id.as_deref())
+    }
+
+    /// For an app installed by a Homebrew cask, the plan is the cask's `Caskroom` folder and the bundle,
+    /// which `brew uninstall --cask` removes (with the launch agents and packages its cask declares),
+    /// plus the data the app left in `~/Library` and `/Library`. Other apps get the `standard_plan`.
+    fn plan(&self) -> UninstallPlan {
+        let Some(cask) = &self.cask else {
+            return standard_plan(self);
+        };
+        let mut paths = expand_path_patterns(self.find_related_paths());
+        paths.push(cask.path.clone());
+        UninstallPlan::new(&self.name, paths)
+    }
+
+    /// An app installed by a Homebrew cask is removed with `brew uninstall --cask` first, so Homebrew's
+    /// bookkeeping stays consistent; only the leftover data is then removed from the plan.
+    fn execute(
+        &self,
+        plan: &UninstallPlan,
+        runner: &dyn CommandRunner,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (Some(cask), Some(bundle)) = (&self.cask, &self.bundle) else {
+            return plan.execute(runner, dry_run);
+        };
+        brew_uninstall(runner, cask, self.autoremove, dry_run)?;
+        plan.without(&[cask.path.as_path(), bundle.path.as_path()]).execute(runner, dry_run)
+    }
+
     /// Discovers common file system paths related to a macOS GUI application.
     /// This includes the main application bundle, various support files, preferences, caches, and logs.
+    ///
+    /// Data stored under the bundle identifier (preferences, containers, caches, saved state, HTTP storages,
+    /// WebKit data) is located with the `CFBundleIdentifier` of the bundle; folders named after the app
+    /// use the bundle name, `CFBundleName` and `CFBundleDisplayName`.
     fn find_related_paths(&self) -> Vec<PathBuf> {
-        let home = std::env::var("HOME").unwrap_or_default(); // Get the user's home directory.
+        let home = self.home.display(); // The user's home directory.
+
+        // Names the app may use for its folders: the bundle name, `CFBundleName` and `CFBundleDisplayName`
+        // (e.g. "Visual Studio Code" stores its data in "Code"). `CFBundleExecutable` is not one of them:
+        // executables are often generic ("Electron", "applet", "launcher") and shared by unrelated apps.
+        let mut names = vec![self.name.clone()];
+        if let Some(bundle) = &self.bundle {
+            names.extend(bundle.bundle_names.iter().cloned());
+        }
+        let bundle_id = self.bundle.as_ref().and_then(|b| b.bundle_id.as_deref());
 
         let mut paths = vec![
             // 1. Main Application Bundle: The primary location of the `.app` file.
-            PathBuf::from(format!("/Applications/{}.app", self.name)),
-
-            // 2. Application Support Data: Configuration files, user data, etc.
-            //    Can be system-wide (`/Library/`) or user-specific (`~/Library/`).
-            PathBuf::from(format!("/Library/Application Support/{}", self.name)),
-            PathBuf::from(format!("{}/Library/Application Support/{}", home, self.name)),
-
-            // 3. Preferences (Property List files - .plist): Store application settings.
-            //    Often follow a reverse-domain name convention (e.g., com.apple.Safari.plist).
-            //    Using the app name as a heuristic; a more robust solution uses CFBundleIdentifier.
-            PathBuf::from(format!("/Library/Preferences/com.{}.plist", self.name)), // System-wide preferences.
-            PathBuf::from(format!("{}/Library/Preferences/com.{}.plist", home, self.name)), // User-specific preferences.
+            self.bundle
+                .as_ref()
+                .map(|b| b.path.clone())
+                .unwrap_or_else(|| PathBuf::from(format!("/Applications/{}.app", self.name))),
+        ];
 
-            // 4. Caches: Temporary files for faster performance.
-            PathBuf::from(format!("{}/Library/Caches/{}", home, self.name)), // User-specific caches.
+        for name in &names {
+            paths.extend(vec![
+                // 2. Application Support Data: Configuration files, user data, etc.
+                //    Can be system-wide (`/Library/`) or user-specific (`~/Library/`).
+                PathBuf::from(format!("/Library/Application Support/{}", name)),
+                PathBuf::from(format!("{}/Library/Application Support/{}", home, name)),
 
-            // 5. Logs: Application log files.
-            PathBuf::from(format!("{}/Library/Logs/{}", home, self.name)), // User-specific logs.
+                // 3. Caches: Temporary files for faster performance.
+                PathBuf::from(format!("{}/Library/Caches/{}", home, name)), // User-specific caches.
 
-            // 6. Containers (for sandboxed applications): Applications run in isolated environments.
-            //    These paths are based on the application's Bundle Identifier (e.g., `com.yourcompany.yourapp`).
-            //    The current implementation uses a wildcard heuristic based on the app name.
-            //    TODO: Implement parsing of the app's `Info.plist` to obtain the `CFBundleIdentifier`
-            //    for precise container path construction (e.g., `format!("{}/Library/Containers/{}", home, bundle_id)`).
-            PathBuf::from(format!("{}/Library/Containers/{}.*", home, self.name)), // Heuristic: Matches containers starting with app name.
+                // 4. Logs: Application log files.
+                PathBuf::from(format!("{}/Library/Logs/{}", home, name)), // User-specific logs.
+            ]);
+        }
 
-            // 7. Group Containers (for apps sharing data): Used by multiple apps from the same developer.
-            //    Also based on a group identifier. Heuristic used here.
-            //    TODO: Similar to individual containers, more precise identification requires group IDs.
-            PathBuf::from(format!("{}/Library/Group Containers/*{}.*", home, self.name)), // Heuristic: Matches group containers containing app name.
+        match bundle_id {
+            // 5. Data named after the bundle identifier (e.g. `com.tinyspeck.slackmacgap`).
+            Some(id) => paths.extend(vec![
+                // Preferences (Property List files - .plist): Store application settings.
+                PathBuf::from(format!("/Library/Preferences/{}.plist", id)), // System-wide preferences.
+                PathBuf::from(format!("{}/Library/Preferences/{}.plist", home, id)), // User-specific preferences.
+                PathBuf::from(format!("{}/Library/Preferences/ByHost/{}.*.plist", home, id)), // Per-machine preferences.
+                // Containers (for sandboxed applications): Applications run in isolated environments.
+                PathBuf::from(format!("{}/Library/Containers/{}", home, id)),
+                PathBuf::from(format!("{}/Library/Containers/{}.*", home, id)), // App extensions and helpers.
+                PathBuf::from(format!("{}/Library/Group Containers/*.{}", home, id)), // Prefixed by the team ID.
+                PathBuf::from(format!("{}/Library/Application Scripts/{}", home, id)),
+                // Caches and web data stored by the system frameworks on behalf of the app.
+                PathBuf::from(format!("{}/Library/Caches/{}", home, id)),
+                PathBuf::from(format!("{}/Library/HTTPStorages/{}", home, id)),
+                PathBuf::from(format!("{}/Library/HTTPStorages/{}.binarycookies", home, id)),
+                PathBuf::from(format!("{}/Library/Cookies/{}.binarycookies", home, id)),
+                PathBuf::from(format!("{}/Library/WebKit/{}", home, id)),
+                // Saved Application State: Window state restored when the app is relaunched.
+                PathBuf::from(format!("{}/Library/Saved Application State/{}.savedState", home, id)),
+                PathBuf::from(format!("{}/Library/Application Support/{}", home, id)),
+            ]),
+            // Without an `Info.plist`, fall back to guessing the preferences and containers from the app name.
+            // These heuristics may match other apps' data, so they are never used when the bundle ID is known.
+            None => paths.extend(vec![
+                PathBuf::from(format!("/Library/Preferences/com.{}.plist", self.name)), // System-wide preferences.
+                PathBuf::from(format!("{}/Library/Preferences/com.{}.plist", home, self.name)), // User-specific preferences.
+
+                // 6. Containers (for sandboxed applications) whose identifier starts with the app name.
+                PathBuf::from(format!("{}/Library/Containers/{}.*", home, self.name)), // Heuristic: Matches containers starting with app name.
+
+                // 7. Group Containers (for apps sharing data): Used by multiple apps from the same developer.
+                PathBuf::from(format!("{}/Library/Group Containers/*{}.*", home, self.name)), // Heuristic: Matches group containers containing app name.
+            ]),
+        }
 
+        paths.extend(vec![
             // 8. Crash Reporter Logs: Plist files generated when an application crashes.
             PathBuf::from(format!("{}/Library/Application Support/CrashReporter/{}_*.plist", home, self.name)),
 
@@ -256,13 +674,15 @@ impl Uninstaller for MacApp {
             PathBuf::from(format!("{}/Library/QuickLook/{}", home, self.name)),
             PathBuf::from(format!("{}/Library/Internet Plug-Ins/{}", home, self.name)),
             PathBuf::from(format!("{}/Library/Fonts/{}", home, self.name)),
-        ];
+        ]);
 
         // Filter out any paths that might have resulted in empty strings (e.g., if `home` was empty
         // and some formatting created an empty path component).
         paths.retain(|p| !p.as_os_str().is_empty());
 
-        paths // Return the comprehensive list of paths.
+        // Return the comprehensive list of paths. Entries with wildcards are patterns: they are expanded
+        // by `expand_path_patterns`, which also removes the duplicates of names that coincide.
+        paths
     }
 }
 
@@ -271,17 +691,45 @@ impl Uninstaller for MacApp {
 /// and its associated files should be uninstalled.
 pub struct CliTool {
     name: String, // Stores the name of the command-line tool (e.g., "brew", "git").
+    // The Homebrew formula or cask providing the tool, if it is managed by Homebrew.
+    homebrew: Option<HomebrewPackage>,
+    // The language package manager (cargo, npm, pipx, go, gem) that installed the tool, if any.
+    managed: Option<ManagedTool>,
+    // Whether `brew autoremove` runs after `brew uninstall`, to remove dependencies nothing needs anymore.
+    autoremove: bool,
 }
 
 impl CliTool {
     /// Creates a new `CliTool` uninstaller instance.
+    ///
+    /// Tools managed by Homebrew (see `find_homebrew_package`) are uninstalled with `brew uninstall`,
+    /// tools installed by a language package manager (see `detect_managed_tool`) with that manager;
+    /// other tools by removing their files.
     /// # Arguments
     /// * `name` - The name of the CLI tool.
     pub fn new(name: &str) -> Self {
+        let homebrew = find_homebrew_package(name);
+        let managed = if homebrew.is_none() {
+            find_on_path(name).and_then(|binary| detect_managed_tool(&binary))
+        } else {
+            None
+        };
+        if let Some(tool) = &managed {
+            log_debug!("'{}' was installed by {} as {}", name, tool.manager, tool.package.bright_white());
+        }
         Self {
             name: name.to_string(), // Convert the string slice to an owned String.
+            homebrew,
+            managed,
+            autoremove: false,
         }
     }
+
+    /// Runs `brew autoremove` after uninstalling a Homebrew-managed tool when `autoremove` is set.
+    pub fn with_autoremove(mut self, autoremove: bool) -> Self {
+        self.autoremove = autoremove;
+        self
+    }
 }
 
 // Implement the `Uninstaller` trait for `CliTool`.
@@ -291,15 +739,44 @@ impl Uninstaller for CliTool {
         &self.name // Return a reference to the stored tool name.
     }
 
+    /// For a Homebrew-managed tool, the plan is its `Cellar` or `Caskroom` folder: Homebrew removes the rest.
+    /// For a tool installed by a language package manager, it is what that manager removes.
+    /// Other tools are planned from their own files only (see `find_related_paths`): without a bundle
+    /// identifier, no launch agent or package receipt can be told apart from another vendor's.
+    fn plan(&self) -> UninstallPlan {
+        match (&self.homebrew, &self.managed) {
+            (Some(package), _) => UninstallPlan::new(&self.name, vec![package.path.clone()]),
+            (None, Some(tool)) => UninstallPlan::new(&self.name, tool.paths.clone()),
+            (None, None) => UninstallPlan::new(&self.name, self.find_related_paths()),
+        }
+    }
+
+    /// Homebrew-managed tools are removed with `brew uninstall` (and `brew autoremove`), which also cleans
+    /// up Homebrew's own bookkeeping, and tools of language package managers with `uninstall_managed_tool`,
+    /// which keeps the manager's metadata consistent; other tools by removing the paths of the plan.
+    fn execute(
+        &self,
+        plan: &UninstallPlan,
+        runner: &dyn CommandRunner,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match (&self.homebrew, &self.managed) {
+            (Some(package), _) => Ok(brew_uninstall(runner, package, self.autoremove, dry_run)?),
+            (None, Some(tool)) => Ok(uninstall_managed_tool(runner, tool, dry_run)?),
+            (None, None) => plan.execute(runner, dry_run),
+        }
+    }
+
     /// Discovers common file system paths where command-line tools and their related files might be found.
     /// This includes common binary locations, libraries, documentation, and configuration files.
     fn find_related_paths(&self) -> Vec<PathBuf> {
-        let mut paths = vec![
-            // 1. Common Binary Locations: Where executables are typically installed.
-            PathBuf::from(format!("/usr/local/bin/{}", self.name)), // Common for user-installed binaries.
-            PathBuf::from(format!("/usr/bin/{}", self.name)), // System binaries (less common for uninstallation).
-            PathBuf::from(format!("/opt/homebrew/bin/{}", self.name)), // Homebrew's default binary symlink path.
+        // 1. Common Binary Locations: Where executables are typically installed.
+        let mut paths: Vec<PathBuf> = BIN_DIRS
+            .iter()
+            .map(|dir| PathBuf::from(format!("{}/{}", dir, self.name)))
+            .collect();
 
+        paths.extend(vec![
             // 2. Libraries and Frameworks: Shared components used by the tool.
             PathBuf::from(format!("/usr/local/lib/{}", self.name)), // Libraries specific to the tool.
             PathBuf::from(format!("/Library/Frameworks/{}.framework", self.name)), // System-wide frameworks.
@@ -312,21 +789,82 @@ impl Uninstaller for CliTool {
             // 4. Configuration Files: Settings and configuration for the tool.
             PathBuf::from(format!("/etc/{}", self.name)), // System-wide configuration.
             PathBuf::from(format!("/etc/paths.d/{}", self.name)), // Files that add directories to the system's PATH.
-        ];
+        ]);
 
-        // 5. Homebrew Specific Paths: If Homebrew is installed, check its cellar for the tool's actual installation directory.
-        //    Homebrew installs tools into a "Cellar" and then symlinks them to `/opt/homebrew/bin` or `/usr/local/bin`.
-        //    Checking the Cellar ensures the original installation directory is targeted for removal.
-        if PathBuf::from("/opt/homebrew/Cellar").exists() {
-            paths.push(PathBuf::from(format!("/opt/homebrew/Cellar/{}", self.name)));
-        }
-        if PathBuf::from("/usr/local/Cellar").exists() { // For older Homebrew installations or specific setups.
-            paths.push(PathBuf::from(format!("/usr/local/Cellar/{}", self.name)));
-        }
+        // Homebrew's Cellar isn't listed here: tools managed by Homebrew are uninstalled with `brew` (see `plan`).
 
         // Filter out any paths that might have resulted in empty strings.
         paths.retain(|p| !p.as_os_str().is_empty());
 
         paths // Return the comprehensive list of paths.
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::command::testing::RecordingRunner;
+    use std::{env, process};
+
+    /// Creates an empty fixture folder, unique to this test process.
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("osx-test-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn cask_apps_are_removed_by_brew_only_once() {
+        let fixture = fixture_dir("cask-app");
+        let bundle = fixture.join("Applications/Slack.app");
+        let caskroom = fixture.join("Caskroom/slack");
+        let cache = fixture.join("Library/Caches/com.tinyspeck.slackmacgap");
+        for dir in [&bundle, &caskroom, &cache] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let app = MacApp {
+            name: "Slack".to_string(),
+            bundle: Some(InstalledApp {
+                name: "Slack".to_string(),
+                path: bundle.clone(),
+                bundle_id: Some("com.tinyspeck.slackmacgap".to_string()),
+                bundle_names: vec![],
+                executable: None,
+                version: None,
+            }),
+            cask: Some(HomebrewPackage {
+                name: "slack".to_string(),
+                cask: true,
+                path: caskroom.clone(),
+            }),
+            autoremove: false,
+            home: fixture.clone(),
+        };
+        let plan = UninstallPlan::new("Slack", vec![bundle.clone(), caskroom.clone(), cache.clone()]);
+
+        let runner = RecordingRunner::succeeding();
+        app.execute(&plan, &runner, false).unwrap();
+        assert_eq!(runner.commands(), vec!["brew uninstall --cask slack"]);
+        // The bundle and the `Caskroom` are left to `brew`; the data it doesn't know about is removed.
+        assert!(bundle.exists() && caskroom.exists());
+        assert!(!cache.exists());
+    }
+
+    #[test]
+    fn receipts_of_vendors_are_not_matched_by_name() {
+        let receipts = fixture_dir("receipts-by-name");
+        for receipt in ["com.google.Chrome", "com.google.Keystone"] {
+            fs::write(receipts.join(format!("{}.plist", receipt)), "").unwrap();
+            fs::write(receipts.join(format!("{}.bom", receipt)), "").unwrap();
+        }
+        let dirs = [receipts.as_path()];
+
+        // `osx uninstall google` has no bundle identifier: neither receipt is selected.
+        assert!(find_pkg_receipts(&dirs, None).is_empty());
+        assert!(find_pkg_receipts(&dirs, Some("google")).is_empty());
+
+        let mut chrome = find_pkg_receipts(&dirs, Some("com.google.Chrome"));
+        chrome.sort();
+        assert_eq!(chrome, vec![receipts.join("com.google.Chrome.bom"), receipts.join("com.google.Chrome.plist")]);
+    }
+}
@@ -0,0 +1,99 @@
+// This file provides a small abstraction over running external commands (`tmutil`, `launchctl`, `brew`, ...).
+// Code that shells out takes a `&dyn CommandRunner` instead of calling `std::process::Command` directly,
+// so its output parsing and error handling can be exercised with canned output on any platform.
+
+use crate::log_debug;
+use colored::Colorize;
+use std::{io, process::Command};
+
+/// The captured result of a finished command.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// `true` if the command exited with status 0.
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs external commands and captures their output.
+pub trait CommandRunner: Send + Sync {
+    /// Runs `program` with `args` and waits for it to finish.
+    /// Returns an error only if the command could not be started.
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput>;
+
+    /// Runs `program` with `args` and returns its standard output, or an error carrying its
+    /// standard error when it exits with a non-zero status.
+    fn run_checked(&self, program: &str, args: &[&str]) -> io::Result<String> {
+        let output = self.run(program, args)?;
+        if output.success {
+            Ok(output.stdout)
+        } else {
+            let message = if output.stderr.trim().is_empty() { &output.stdout } else { &output.stderr };
+            Err(io::Error::other(format!("`{} {}` failed: {}", program, args.join(" "), message.trim())))
+        }
+    }
+}
+
+/// Runs commands on the actual system through `std::process::Command`.
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        log_debug!("Running: {} {}", program, args.join(" "));
+        let output = Command::new(program).args(args).output()?;
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+/// Test doubles shared by the unit tests of the modules that run commands.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::{CommandOutput, CommandRunner};
+    use std::{io, sync::Mutex};
+
+    /// A runner that records the commands instead of running them, and answers them with canned outputs.
+    pub(crate) struct RecordingRunner {
+        /// Outputs of the successive commands; the last one answers every further command.
+        outputs: Vec<CommandOutput>,
+        commands: Mutex<Vec<String>>,
+    }
+
+    impl RecordingRunner {
+        /// A runner whose commands all succeed, with no output.
+        pub(crate) fn succeeding() -> Self {
+            Self::answering(CommandOutput { success: true, ..Default::default() })
+        }
+
+        /// A runner whose commands all fail with `stderr`.
+        pub(crate) fn failing(stderr: &str) -> Self {
+            Self::answering(CommandOutput { success: false, stderr: stderr.to_string(), ..Default::default() })
+        }
+
+        pub(crate) fn answering(output: CommandOutput) -> Self {
+            Self::answering_in_turn(vec![output])
+        }
+
+        /// A runner answering its first command with `outputs[0]`, the second with `outputs[1]`, and so on.
+        pub(crate) fn answering_in_turn(outputs: Vec<CommandOutput>) -> Self {
+            RecordingRunner { outputs, commands: Mutex::new(Vec::new()) }
+        }
+
+        /// The commands run so far, as `program arg1 arg2...`.
+        pub(crate) fn commands(&self) -> Vec<String> {
+            self.commands.lock().unwrap().clone()
+        }
+    }
+
+    impl CommandRunner for RecordingRunner {
+        fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+            let mut commands = self.commands.lock().unwrap();
+            let output = self.outputs[commands.len().min(self.outputs.len() - 1)].clone();
+            commands.push(format!("{} {}", program, args.join(" ")));
+            Ok(output)
+        }
+    }
+}
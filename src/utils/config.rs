@@ -0,0 +1,44 @@
+// This file provides small helpers for reading user configuration from environment variables.
+// The tool follows the `OSX_*` convention already used for `OSX_SHOW_WARNINGS`,
+// `OSX_SHOW_DETAILS` and `OSX_SHOW_SKIPPED`, so cleaners can be tuned without new CLI flags.
+
+use std::env;
+
+/// Reads a comma-separated list from the environment variable `name` (see `parse_list`).
+/// A missing variable yields an empty vector.
+///
+/// # Example
+///
+/// ```
+/// use osx::utils::config::env_list;
+/// assert!(env_list("OSX_DOC_EXAMPLE_MISSING").is_empty());
+/// ```
+pub fn env_list(name: &str) -> Vec<String> {
+    env::var(name).map(|value| parse_list(&value)).unwrap_or_default()
+}
+
+/// Splits a comma-separated list. Entries are trimmed and empty entries are dropped.
+///
+/// # Example
+///
+/// ```
+/// use osx::utils::config::parse_list;
+/// assert_eq!(parse_list("Slack, ,Discord"), vec!["Slack", "Discord"]);
+/// assert!(parse_list("").is_empty());
+/// ```
+pub fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// Reads an unsigned integer from the environment variable `name`, falling back to `default`
+/// when the variable is missing or cannot be parsed.
+pub fn env_u64(name: &str, default: u64) -> u64 {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default)
+}
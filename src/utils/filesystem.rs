@@ -59,7 +59,8 @@ pub fn remove_path(path: &Path, dry_run: bool) -> io::Result<()> {
     }
 
     // Check if the path exists. If it doesn't, there's nothing to do, so return Ok immediately.
-    if !path.exists() {
+    // `symlink_metadata` is used so that broken symlinks (whose target is missing) still count as existing.
+    if fs::symlink_metadata(path).is_err() {
         log_debug!("Path does not exist: {}", path.display()); // Log that the path was not found.
         return Ok(()); // Return success as the desired state (path removed) is already met.
     }
@@ -188,3 +189,84 @@ pub fn split_filenames(key: &str) -> (String, String) {
         (String::new(), key.to_string())
     }
 }
+
+/// Returns the effective user id of the current process (`0` when running as root).
+pub fn current_uid() -> u32 {
+    // SAFETY: `geteuid` has no preconditions and cannot fail.
+    unsafe { libc::geteuid() }
+}
+
+/// Returns the user id of the user who invoked the tool: `SUDO_UID` when running through `sudo`,
+/// the effective user id otherwise. Per-user data (trash, GUI session) belongs to this user.
+pub fn invoking_uid() -> u32 {
+    std::env::var("SUDO_UID")
+        .ok()
+        .and_then(|uid| uid.parse().ok())
+        .unwrap_or_else(current_uid)
+}
+
+/// Checks whether the current process may write to `path`, using the `access(2)` system call.
+/// Unlike comparing owners, this honors group permissions and root privileges.
+///
+/// # Example
+///
+/// ```
+/// use osx::utils::filesystem::is_writable;
+/// assert!(is_writable(&std::env::temp_dir()));
+/// assert!(!is_writable(std::path::Path::new("/nonexistent/osx-doc")));
+/// ```
+pub fn is_writable(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `path` is a valid NUL-terminated string that outlives the call.
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+/// Mount properties of the volume holding a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VolumeFlags {
+    /// The volume is mounted read-only.
+    pub read_only: bool,
+    /// The volume is stored locally, as opposed to a network share (SMB, AFP, NFS, WebDAV).
+    pub local: bool,
+}
+
+/// Returns the mount properties of the volume holding `path`.
+/// Network volumes can only be told apart on macOS; elsewhere every volume is reported as local.
+///
+/// # Example
+///
+/// ```
+/// use osx::utils::filesystem::volume_flags;
+/// let flags = volume_flags(&std::env::temp_dir()).unwrap();
+/// assert!(!flags.read_only);
+/// assert!(volume_flags(std::path::Path::new("/nonexistent/osx-doc")).is_err());
+/// ```
+pub fn volume_flags(path: &Path) -> io::Result<VolumeFlags> {
+    use std::os::unix::ffi::OsStrExt;
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    // SAFETY: `c_path` is a valid NUL-terminated string and `stat` is a properly sized output buffer.
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let read_only = stat.f_flag & libc::ST_RDONLY != 0;
+
+    #[cfg(target_os = "macos")]
+    let local = {
+        // SAFETY: same as above, for the macOS-specific `statfs`, which exposes `MNT_LOCAL`.
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        stat.f_flags & libc::MNT_LOCAL as u32 != 0
+    };
+    #[cfg(not(target_os = "macos"))]
+    let local = true;
+
+    Ok(VolumeFlags { read_only, local })
+}
@@ -1 +1,3 @@
 pub mod filesystem;
+pub mod config;
+pub mod command;